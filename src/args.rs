@@ -5,33 +5,61 @@ use clap::{
     Arg, ArgAction, ArgMatches, ValueEnum,
 };
 
-use crate::Filter;
+use crate::{ColorChoice, Emit, Filter, Format, LinkChoice, Only, Powerset, Remap, SortBy};
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Tool {
     #[default]
     Clippy,
     Check,
+    Build,
+    /// `cargo test --no-run`, this builds the tests without running them
+    Test,
+    Doc,
 }
 
 impl ValueEnum for Tool {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Clippy, Self::Check]
+        &[
+            Self::Clippy,
+            Self::Check,
+            Self::Build,
+            Self::Test,
+            Self::Doc,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         Some(match self {
             Self::Clippy => PossibleValue::new("clippy"),
             Self::Check => PossibleValue::new("check"),
+            Self::Build => PossibleValue::new("build"),
+            Self::Test => PossibleValue::new("test").help("builds the tests without running them"),
+            Self::Doc => PossibleValue::new("doc"),
         })
     }
 }
 
+#[derive(Debug)]
+pub enum SubCommand {
+    Explain { lint: String },
+    ThemeList,
+    ThemePreview { name: Option<String> },
+    Lsp,
+}
+
 #[derive(Debug)]
 pub struct Args {
-    pub tool: Tool,
+    pub subcommand: Option<SubCommand>,
+    pub tool: Option<Tool>,
     pub nightly: bool,
+    pub toolchain: Option<String>,
+    pub wrapper: Option<Vec<String>>,
+    pub separate_target_dir: bool,
+    pub fresh: bool,
+    pub timeout: Option<u64>,
     pub explain: bool,
+    pub explain_lints: bool,
     pub include_notes: bool,
     pub tests: bool,
     pub examples: bool,
@@ -39,16 +67,34 @@ pub struct Args {
     pub annoying: bool,
     pub more_annoying: bool,
     pub filter: Vec<Filter>,
+    pub only: Vec<Only>,
+    pub only_lint: Vec<String>,
+    pub remap: Vec<Remap>,
     pub warning: Vec<String>,
     pub allow: Vec<String>,
     pub deny: Vec<String>,
+    pub lint_args: Vec<String>,
+    pub cargo_args: Vec<String>,
     pub target: Vec<String>,
     pub all_targets: bool,
     pub features: Vec<String>,
     pub all_features: bool,
     pub no_features: bool,
+    pub powerset: Option<Powerset>,
+    pub exclude_features: Vec<String>,
+    pub matrix: bool,
     pub delimiter: Option<String>,
     pub new_line: bool,
+    pub ascii: bool,
+    pub format: Option<Format>,
+    pub emit: Emit,
+    pub sort: Vec<SortBy>,
+    pub max: Option<usize>,
+    pub first: bool,
+    pub color: Option<ColorChoice>,
+    pub hyperlinks: Option<LinkChoice>,
+    pub editor_url: Option<String>,
+    pub theme: Option<String>,
     pub ignore_config: bool,
     pub print_config_path: bool,
     pub print_default_config: bool,
@@ -63,21 +109,120 @@ impl Args {
                 "simplifies the output of cargo clippy\n\n\
                 this runs clippy (or check) and produces a more compact output",
             )
+            .subcommand(
+                clap::Command::new("explain")
+                    .about("explain a lint or an error code")
+                    .long_about(
+                        "explain a lint or an error code\n\n\
+                        `clippy::*` lints use `cargo clippy --explain`,\n\
+                        error codes (E0308) use `rustc --explain`.\n\
+                        this uses the selected toolchain (e.g. with `--toolchain` or `--nightly`)",
+                    )
+                    .arg(
+                        Arg::new("lint")
+                            .required(true)
+                            .help("the lint or error code, e.g. `clippy::needless_return` or `E0308`"),
+                    ),
+            )
+            .subcommand(
+                clap::Command::new("lsp")
+                    .about("run a language server on stdin and stdout")
+                    .long_about(
+                        "run a language server on stdin and stdout\n\n\
+                        the check runs when a file is saved, with the same options, lints and filters as usual.\n\
+                        each diagnostic includes its lint, with any help and notes as related information",
+                    ),
+            )
+            .subcommand(
+                clap::Command::new("theme")
+                    .about("list or preview themes")
+                    .subcommand_required(true)
+                    .subcommand(
+                        clap::Command::new("list")
+                            .about("list the built-in themes and the ones in the `themes` directory"),
+                    )
+                    .subcommand(
+                        clap::Command::new("preview")
+                            .about("render some sample diagnostics with a theme")
+                            .arg(Arg::new("name").help(
+                                "the theme to preview, defaults to the one that would be used",
+                            )),
+                    ),
+            )
             .arg(
                 Arg::new("tool")
                     .long("tool")
                     .action(ArgAction::Set)
                     .value_parser(EnumValueParser::<Tool>::new())
                     .ignore_case(true)
-                    .default_value("clippy")
-                    .help("specify the tool to use to check for lints"),
+                    .help("specify the tool to use to check for lints [default: clippy]")
+                    .long_help(
+                        "specify the tool to use to check for lints [default: clippy].\n\
+                        only `clippy` takes lint flags directly, the other tools get them through \
+                        `--config build.rustflags` (or `build.rustdocflags` for `doc`), which adds to \
+                        the flags from cargo's configuration.\n\
+                        if `RUSTFLAGS` (or `RUSTDOCFLAGS`) is set they are added to it instead, and \
+                        cargo ignores the flags in its configuration, like it always does.",
+                    ),
             )
             .arg(
                 Arg::new("nightly")
                     .short('n')
                     .long("nightly")
                     .action(ArgAction::SetTrue)
-                    .help("use the installed nightly version of clippy"),
+                    .help("use the installed nightly version of clippy, this is the same as `--toolchain nightly`"),
+            )
+            .arg(
+                Arg::new("toolchain")
+                    .long("toolchain")
+                    .value_name("name")
+                    .conflicts_with("nightly")
+                    .help("use a specific toolchain, e.g. `beta`, `1.74.0` or `nightly-2024-05-01`")
+                    .long_help(
+                        "use a specific toolchain, e.g. `beta`, `1.74.0` or `nightly-2024-05-01`.\n\
+                        without this, the channel from the project's `rust-toolchain.toml` (or `rust-toolchain`) is used.\n\
+                        otherwise rustup picks the toolchain",
+                    ),
+            )
+            .arg(
+                Arg::new("wrapper")
+                    .long("wrapper")
+                    .value_name("command")
+                    .help("run this in place of cargo, e.g. `cross` or `cargo zigbuild`")
+                    .long_help(
+                        "run this in place of cargo, e.g. `cross` or `cargo zigbuild`.\n\
+                        it gets the same arguments as cargo would, so it has to write cargo's json messages.\n\
+                        the command is split on whitespace, `wrapper` in the configuration file can also be a list.\n\
+                        a toolchain is passed with `RUSTUP_TOOLCHAIN`",
+                    ),
+            )
+            .arg(
+                Arg::new("separate_target_dir")
+                    .long("separate-target-dir")
+                    .action(ArgAction::SetTrue)
+                    .help("build in `target/ccs` so rust-analyzer's cache isn't invalidated")
+                    .long_help(
+                        "build in `target/ccs` so rust-analyzer's cache isn't invalidated.\n\
+                        each toolchain and set of lint flags gets its own directory in there, \
+                        so switching between e.g. `-y` and `-Y` doesn't rebuild everything either",
+                    ),
+            )
+            .arg(
+                Arg::new("fresh")
+                    .long("fresh")
+                    .action(ArgAction::SetTrue)
+                    .help("clean the workspace members first, so cached lints aren't replayed")
+                    .long_help(
+                        "clean the workspace members first, so cached lints aren't replayed.\n\
+                        this runs `cargo clean -p` for each member, dependencies don't get rebuilt",
+                    ),
+            )
+            .arg(
+                Arg::new("timeout")
+                    .long("timeout")
+                    .value_name("seconds")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .help("kill cargo if it takes longer than this"),
             )
             .arg(
                 Arg::new("tests")
@@ -131,6 +276,58 @@ impl Args {
                     .help_heading("targets")
                     .help("disable all features"),
             )
+            .arg(
+                Arg::new("each_feature")
+                    .long("each-feature")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["features", "all_features", "no_default_features"])
+                    .help_heading("targets")
+                    .help("check each feature on its own, and merge the results")
+                    .long_help(
+                        "check each feature on its own, and merge the results.\n\
+                        this also checks without any features and with the default ones. \
+                        diagnostics that don't show up in every run are tagged with the features that produce them",
+                    ),
+            )
+            .arg(
+                Arg::new("feature_powerset")
+                    .long("feature-powerset")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["each_feature", "features", "all_features", "no_default_features"])
+                    .help_heading("targets")
+                    .help("check every combination of features, and merge the results"),
+            )
+            .arg(
+                Arg::new("depth")
+                    .long("depth")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(usize))
+                    .requires("feature_powerset")
+                    .help_heading("targets")
+                    .help("the most features to combine with `--feature-powerset`"),
+            )
+            .arg(
+                Arg::new("exclude_features")
+                    .long("exclude-features")
+                    .value_name("feature")
+                    .action(ArgAction::Append)
+                    .value_delimiter(',')
+                    .help_heading("targets")
+                    .help("features to leave out of `--each-feature` and `--feature-powerset`"),
+            )
+            .arg(
+                Arg::new("matrix")
+                    .long("matrix")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["each_feature", "feature_powerset"])
+                    .help_heading("targets")
+                    .help("check each target and toolchain in the configuration's `[matrix]`, and merge the results")
+                    .long_help(
+                        "check each target and toolchain in the configuration's `[matrix]`, and merge the results.\n\
+                        diagnostics that don't show up in every cell are tagged with the cells that produce them.\n\
+                        the cells are checked in parallel with `--separate-target-dir`, otherwise one at a time",
+                    ),
+            )
             .arg(
                 Arg::new("path")
                     .short('p')
@@ -166,8 +363,51 @@ impl Args {
                     .help_heading("controlling lints")
                     .help("ignore a specific warning or error")
                     .long_help(
-                        "syntax: (warning|error)=(named_lint|all).\n\
-                        example: -f error=all -f warning=unused_imports",
+                        "syntax: (warning|error)=(named_lint|all), or an expression.\n\
+                        example: -f error=all -f warning=unused_imports\n\n\
+                        expressions match on `level`, `lint` (or `code`), `path` and `message`\n\
+                        using `=`, `!=`, `~` (a glob, or a /regex/) and `!~`,\n\
+                        combined with `and`, `or`, `not` and parentheses.\n\
+                        example: -f 'level=warning and lint~\"clippy::needless_*\" and not path~\"tests/**\"'\n\
+                        example: -f 'message~/deprecated/ or code=E0308'",
+                    ),
+            )
+            .arg(
+                Arg::new("only")
+                    .long("only")
+                    .value_name("lint or group")
+                    .value_parser(ValueParser::new(Only::from_str))
+                    .action(ArgAction::Append)
+                    .help_heading("controlling lints")
+                    .help("only show this lint, or the lints in this group")
+                    .long_help(
+                        "only show this lint, or the lints in this group. everything else is hidden.\n\
+                        groups are things like `clippy::perf`, `clippy::pedantic` or `unused`.\n\
+                        errors without a code are always shown.\n\
+                        example: --only clippy::perf --only dead_code",
+                    ),
+            )
+            .arg(
+                Arg::new("only_lint")
+                    .long("only-lint")
+                    .value_name("lint")
+                    .action(ArgAction::Append)
+                    .help_heading("controlling lints")
+                    .help("only show this specific lint, without treating it as a group"),
+            )
+            .arg(
+                Arg::new("remap")
+                    .long("remap")
+                    .value_name("lint=level")
+                    .value_parser(ValueParser::new(Remap::from_str))
+                    .action(ArgAction::Append)
+                    .help_heading("controlling lints")
+                    .help("show a lint (or group) with a different level")
+                    .long_help(
+                        "show a lint (or the lints in a group) with a different level.\n\
+                        this only changes what is displayed, it doesn't recompile anything.\n\
+                        levels: error | warning | note | help\n\
+                        example: --remap clippy::todo=error --remap dead_code=note",
                     ),
             )
             .arg(
@@ -197,6 +437,20 @@ impl Args {
                     .help_heading("controlling lints")
                     .help("additional deny lints to use"),
             )
+            .arg(
+                Arg::new("lint_args")
+                    .long("lint-args")
+                    .value_name("args")
+                    .action(ArgAction::Append)
+                    .allow_hyphen_values(true)
+                    .help_heading("controlling lints")
+                    .help("raw arguments for the compiler, e.g. `--lint-args \"-Z macro-backtrace\"`")
+                    .long_help(
+                        "raw arguments for the compiler, e.g. `--lint-args \"-Z macro-backtrace\"`.\n\
+                        these are split on whitespace and go after cargo's `--`, following the lints.\n\
+                        for tools other than `clippy` they are added to the rustflags, like the lints",
+                    ),
+            )
             .arg(
                 Arg::new("explain")
                     .short('e')
@@ -206,6 +460,13 @@ impl Args {
                     .help("use the `explain` format")
                     .long_help("include a snippet of the code if available"),
             )
+            .arg(
+                Arg::new("explain_lints")
+                    .long("explain-lints")
+                    .action(ArgAction::SetTrue)
+                    .help_heading("appearance")
+                    .help("explain each lint the first time it appears"),
+            )
             .arg(
                 Arg::new("include_notes")
                     .short('i')
@@ -234,6 +495,128 @@ impl Args {
                     .help_heading("appearance")
                     .help("append a new line interspersed with each item"),
             )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(EnumValueParser::<Format>::new())
+                    .ignore_case(true)
+                    .help_heading("appearance")
+                    .help("how each diagnostic is written [default: human]")
+                    .long_help(
+                        "how each diagnostic is written [default: human].\n\
+                        `line` writes `path:line:col: level[lint]: message` without any colors,\n\
+                        this works with vim's `:cexpr`, emacs's `compilation-mode` and vscode's problem matchers.\n\
+                        with --include, the help and notes for each diagnostic are written as their own lines",
+                    ),
+            )
+            .arg(
+                Arg::new("emit")
+                    .long("emit")
+                    .value_name("what")
+                    .value_parser(EnumValueParser::<Emit>::new())
+                    .ignore_case(true)
+                    .help_heading("appearance")
+                    .help("what is written to stdout [default: rendered]")
+                    .long_help(
+                        "what is written to stdout [default: rendered].\n\
+                        `cargo-json` writes the messages from cargo as they were, after the filters, lints and remaps are applied.\n\
+                        a remapped message only has its `level`, and the level at the start of `rendered`, changed.\n\
+                        this can be used as rust-analyzer's `check.overrideCommand`, e.g.\n\
+                        [\"ccs\", \"--emit\", \"cargo-json\"]",
+                    ),
+            )
+            .arg(
+                Arg::new("ascii")
+                    .long("ascii")
+                    .action(ArgAction::SetTrue)
+                    .help_heading("appearance")
+                    .help("only use ASCII, mark highlights with carets and always spell out the level")
+                    .long_help(
+                        "only use ASCII, mark highlights with carets and always spell out the level.\n\
+                        this doesn't rely on color or non-ASCII glyphs, so it works with screen readers and limited consoles.\n\
+                        this is used automatically when the locale (or on windows, the console) isn't UTF-8",
+                    ),
+            )
+            .arg(
+                Arg::new("sort")
+                    .long("sort")
+                    .value_name("key")
+                    .value_parser(EnumValueParser::<SortBy>::new())
+                    .ignore_case(true)
+                    .action(ArgAction::Append)
+                    .help_heading("appearance")
+                    .help("sort the diagnostics, multiple keys are applied in order")
+                    .long_help(
+                        "sort the diagnostics, multiple keys are applied in order.\n\
+                        example: --sort severity --sort line",
+                    ),
+            )
+            .arg(
+                Arg::new("max")
+                    .long("max")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(usize))
+                    .help_heading("appearance")
+                    .help("stop after showing this many diagnostics"),
+            )
+            .arg(
+                Arg::new("first")
+                    .long("first")
+                    .alias("fail-fast")
+                    .action(ArgAction::SetTrue)
+                    .help_heading("appearance")
+                    .help("stop cargo once the first error is seen"),
+            )
+            .arg(
+                Arg::new("color")
+                    .long("color")
+                    .value_name("when")
+                    .value_parser(EnumValueParser::<ColorChoice>::new())
+                    .ignore_case(true)
+                    .help_heading("appearance")
+                    .help("when to color the output [default: auto]")
+                    .long_help(
+                        "when to color the output [default: auto].\n\
+                        `auto` colors the output when it is a terminal, and respects `NO_COLOR` and `CLICOLOR_FORCE`.\n\
+                        those environment variables take precedence over the configuration file",
+                    ),
+            )
+            .arg(
+                Arg::new("hyperlinks")
+                    .long("hyperlinks")
+                    .value_name("when")
+                    .value_parser(EnumValueParser::<LinkChoice>::new())
+                    .ignore_case(true)
+                    .help_heading("appearance")
+                    .help("link locations and lint names, if the terminal supports it [default: auto]"),
+            )
+            .arg(
+                Arg::new("editor_url")
+                    .long("editor-url")
+                    .value_name("template")
+                    .help_heading("appearance")
+                    .help("link locations with this url instead of `file://`")
+                    .long_help(
+                        "link locations with this url instead of `file://`.\n\
+                        `{path}`, `{line}` and `{col}` are replaced.\n\
+                        example: --editor-url 'vscode://file/{path}:{line}:{col}'",
+                    ),
+            )
+            .arg(
+                Arg::new("theme")
+                    .long("theme")
+                    .value_name("name")
+                    .help_heading("appearance")
+                    .help("use a built-in theme, or one from the `themes` directory next to the configuration file")
+                    .long_help(
+                        "use a built-in theme, or one from the `themes` directory next to the configuration file.\n\
+                        `--theme solarized` loads `themes/solarized.toml`, a path to a `.toml` file also works.\n\
+                        a theme file takes precedence over a built-in theme with the same name.\n\
+                        built-in themes: default, dark, light, high-contrast, monochrome.\n\
+                        this replaces the `theme` table in the configuration file",
+                    ),
+            )
             .arg(
                 Arg::new("ignore_config")
                     .long("ignore-config")
@@ -266,6 +649,18 @@ impl Args {
                     .action(ArgAction::SetTrue)
                     .help_heading("meta")
                     .help("print out the command invocation -- don't actually run it"),
+            )
+            .arg(
+                Arg::new("cargo_args")
+                    .value_name("cargo args")
+                    .num_args(1..)
+                    .last(true)
+                    .allow_hyphen_values(true)
+                    .help("arguments for cargo, e.g. `ccs -- --release --locked`")
+                    .long_help(
+                        "arguments for cargo, e.g. `ccs -- --release --locked`.\n\
+                        these go before cargo's `--`, so they can be any of the tool's flags (`--profile`, `--offline`, `-Z ...`)",
+                    ),
             );
 
         let mut matches = cmd.get_matches();
@@ -277,10 +672,34 @@ impl Args {
             matches.remove_many(key).into_iter().flatten().collect()
         }
 
+        let subcommand = match matches.remove_subcommand() {
+            Some((name, mut matches)) if name == "explain" => Some(SubCommand::Explain {
+                lint: matches.remove_one("lint").expect("required argument"),
+            }),
+            Some((name, _)) if name == "lsp" => Some(SubCommand::Lsp),
+            Some((name, mut matches)) if name == "theme" => match matches.remove_subcommand() {
+                Some((name, _)) if name == "list" => Some(SubCommand::ThemeList),
+                Some((name, mut matches)) if name == "preview" => Some(SubCommand::ThemePreview {
+                    name: matches.remove_one("name"),
+                }),
+                _ => unreachable!("a subcommand is required"),
+            },
+            _ => None,
+        };
+
         Self {
-            tool: matches.remove_one("tool").unwrap_or_default(),
+            subcommand,
+            tool: matches.remove_one("tool"),
             nightly: matches.get_flag("nightly"),
+            toolchain: matches.remove_one("toolchain"),
+            wrapper: matches
+                .remove_one::<String>("wrapper")
+                .map(|s| s.split_whitespace().map(String::from).collect()),
+            separate_target_dir: matches.get_flag("separate_target_dir"),
+            fresh: matches.get_flag("fresh"),
+            timeout: matches.remove_one("timeout"),
             explain: matches.get_flag("explain"),
+            explain_lints: matches.get_flag("explain_lints"),
             include_notes: matches.get_flag("include_notes"),
             tests: matches.get_flag("tests"),
             examples: matches.get_flag("examples"),
@@ -288,6 +707,9 @@ impl Args {
             annoying: matches.get_flag("annoying"),
             more_annoying: matches.get_flag("more_annoying"),
             filter: get_many(&mut matches, "filter"),
+            only: get_many(&mut matches, "only"),
+            only_lint: get_many(&mut matches, "only_lint"),
+            remap: get_many(&mut matches, "remap"),
             warning: get_many(&mut matches, "warning"),
             allow: get_many(&mut matches, "allow"),
             deny: get_many(&mut matches, "deny"),
@@ -298,10 +720,33 @@ impl Args {
             no_features: matches.get_flag("no_default_features"),
             delimiter: matches.remove_one("delimiter"),
             new_line: matches.get_flag("new_line"),
+            ascii: matches.get_flag("ascii"),
+            format: matches.remove_one("format"),
+            emit: matches.remove_one("emit").unwrap_or_default(),
+            sort: get_many(&mut matches, "sort"),
+            max: matches.remove_one("max"),
+            powerset: if matches.get_flag("each_feature") {
+                Some(Powerset::EachFeature)
+            } else if matches.get_flag("feature_powerset") {
+                Some(Powerset::FeaturePowerset {
+                    depth: matches.remove_one("depth"),
+                })
+            } else {
+                None
+            },
+            exclude_features: get_many(&mut matches, "exclude_features"),
+            matrix: matches.get_flag("matrix"),
+            first: matches.get_flag("first"),
+            color: matches.remove_one("color"),
+            hyperlinks: matches.remove_one("hyperlinks"),
+            editor_url: matches.remove_one("editor_url"),
+            theme: matches.remove_one("theme"),
             ignore_config: matches.get_flag("ignore_config"),
             print_config_path: matches.get_flag("print_config_path"),
             print_default_config: matches.get_flag("print_default_config"),
             dry_run: matches.get_flag("dry_run"),
+            lint_args: get_many(&mut matches, "lint_args"),
+            cargo_args: get_many(&mut matches, "cargo_args"),
         }
     }
 }
@@ -1,14 +1,23 @@
-use std::{fs::Metadata, io::BufReader, path::PathBuf};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fs::Metadata,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anstream::AutoStream;
+use anyhow::Context as _;
 
 use ccs::{
-    gather_reasons, Args, Command, Config, Extra, Features, IncludeNotes, Options, Reason,
-    RenderOptions, RenderStyle, Target, Theme, Tool, Toolchain,
+    iter_raw_reasons, iter_reasons, Args, Cargo, CargoSource, ColorChoice, Command, Config, Emit,
+    Extra, Features, Format, Hyperlinks, IncludeNotes, Level, Matrix, Message, Options, Powerset,
+    Process, Reason, RenderOptions, RenderStyle, Report, SortBy, SubCommand, Target, Templates,
+    Theme, Tool, Toolchain,
 };
 
 fn try_find_manifest(path: &mut PathBuf) -> anyhow::Result<()> {
-    match path.components().last() {
+    match path.components().next_back() {
         Some(s) if s.as_os_str() == "Cargo.toml" => {}
         Some(..) => {
             anyhow::ensure!(path.is_dir(), "a non-manifest file was provided");
@@ -27,17 +36,493 @@ fn try_find_manifest(path: &mut PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn is_nightly_available() -> bool {
-    ccs::find_cargo(Toolchain::Nightly).is_some()
+// the directory relative locations are resolved from
+fn link_root(manifest: Option<&Path>) -> PathBuf {
+    let dir = manifest
+        .and_then(Path::parent)
+        .filter(|p| !p.as_os_str().is_empty())
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+    std::fs::canonicalize(&dir).unwrap_or(dir)
 }
 
-fn main() -> anyhow::Result<()> {
-    let mut args = Args::parse();
+fn print_theme_report(source: &str, report: &Report) {
+    if report.is_empty() {
+        return;
+    }
 
-    if matches!(args.tool, Tool::Check) && (args.annoying || args.more_annoying) {
-        eprintln!("Error: -y / -Y requires `--tool clippy`");
+    eprintln!("warning: the theme in {source} has some problems:");
+    for line in report.to_string().lines() {
+        eprintln!("  {line}");
+    }
+}
+
+// a theme file takes precedence over a built-in theme with the same name
+fn load_theme(name: &str) -> Theme {
+    let path = Config::get_theme_path(name).filter(|path| path.is_file());
+    let Some(path) = path else {
+        if let Some(theme) = Theme::preset(name) {
+            return theme;
+        }
+
+        let names = Theme::PRESETS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(Config::theme_names())
+            .collect::<Vec<_>>();
+        eprintln!(
+            "unknown theme `{name}`, expected one of: {names}",
+            names = names.join(" | ")
+        );
         std::process::exit(1)
+    };
+
+    let (theme, report) = match Theme::load_file(&path) {
+        Ok(theme) => theme,
+        Err(err) => {
+            eprintln!("cannot load theme `{name}`: {err:#}");
+            std::process::exit(1)
+        }
+    };
+    print_theme_report(&format!("`{name}`"), &report);
+    theme
+}
+
+fn print_themes() {
+    println!("built-in:");
+    for (name, _) in Theme::PRESETS {
+        println!("  {name}");
+    }
+
+    let names = Config::theme_names();
+    if let (Some(dir), false) = (Config::get_themes_dir(), names.is_empty()) {
+        println!("{dir}:", dir = dir.to_string_lossy());
+        for name in names {
+            println!("  {name}");
+        }
+    }
+}
+
+// what cargo produced, either from a single run or merged from several
+struct Output {
+    reasons: Vec<Reason>,
+    // messages cargo wrote to stderr, these aren't sorted or filtered
+    stderr: Vec<Message>,
+    summary: Option<Message>,
+}
+
+// reads the messages that aren't filtered out, stopping at the first error or after `limit`
+fn read_reasons(
+    mut process: Process,
+    render_options: &RenderOptions,
+    first: bool,
+    limit: Option<usize>,
+) -> Output {
+    let stdout = process.take_stdout();
+
+    let mut reasons = vec![];
+    for mut reason in iter_reasons(BufReader::new(stdout)) {
+        let mut is_error = false;
+        if let Reason::CompilerMessage { message } = &mut reason {
+            render_options.remap_level(message);
+            if render_options.is_ignored(message) {
+                continue;
+            }
+            is_error = matches!(message.level, Level::Error);
+        }
+
+        let stop = (first && is_error) || limit.is_some_and(|max| reasons.len() + 1 >= max);
+        reasons.push(reason);
+        if stop {
+            process.kill();
+            break;
+        }
+    }
+
+    let finished = process.finish();
+    let shown_error = reasons.iter().any(|reason| {
+        matches!(reason, Reason::CompilerMessage { message } if matches!(message.level, Level::Error))
+    });
+    let exit_error = finished.exit_error(shown_error);
+    Output {
+        reasons,
+        stderr: finished.messages.into_iter().chain(exit_error).collect(),
+        summary: finished.summary,
+    }
+}
+
+// runs the tool once for each feature set, identical diagnostics are merged and tagged with
+// the sets that produce them. the ones every set produces aren't tagged
+fn check_feature_sets(
+    command: &Command<'_>,
+    opts: &Options,
+    powerset: Powerset,
+    exclude: &[String],
+    render_options: &RenderOptions,
+    first: bool,
+) -> anyhow::Result<Output> {
+    let metadata = ccs::Metadata::load(&opts.cargo, opts.path.as_deref())?;
+    let package = metadata.package(opts.path.as_deref()).with_context(|| {
+        "cannot tell which package's features to check, use `--path` to pick one"
+    })?;
+
+    let runs = powerset
+        .feature_sets(package.features.keys().map(|s| &**s), exclude)
+        .into_iter()
+        .map(|set| {
+            let mut opts = opts.clone();
+            opts.features = set.features;
+            (set.label, opts)
+        })
+        .collect::<Vec<_>>();
+
+    merge_runs(
+        command,
+        runs,
+        "with features: ",
+        false,
+        render_options,
+        first,
+        |labels| format!("features: {labels}", labels = labels.join(", ")),
+    )
+}
+
+// runs the tool for each cell of the matrix, the diagnostics that only some of the cells
+// produce are tagged with them, e.g. `only on wasm32`
+fn check_matrix(
+    command: &Command<'_>,
+    opts: &Options,
+    matrix: &Matrix,
+    cargo_path: Option<&Path>,
+    render_options: &RenderOptions,
+    first: bool,
+) -> anyhow::Result<Output> {
+    anyhow::ensure!(
+        !matrix.is_empty(),
+        "`--matrix` needs `targets` or `toolchains` in the `[matrix]` of the configuration file"
+    );
+
+    // `wasm32` reads better than `wasm32-unknown-unknown`, unless another target has that arch
+    let arch = |triple: &str| triple.split('-').next().unwrap_or(triple).to_string();
+    let short_name = |triple: &str| {
+        let count = matrix
+            .targets
+            .iter()
+            .filter(|other| arch(other) == arch(triple))
+            .count();
+        if count == 1 {
+            arch(triple)
+        } else {
+            triple.to_string()
+        }
+    };
+
+    let toolchains = match &*matrix.toolchains {
+        [] => vec![None],
+        toolchains => toolchains.iter().map(Some).collect(),
+    };
+    let targets = match &*matrix.targets {
+        [] => vec![None],
+        targets => targets.iter().map(Some).collect(),
+    };
+
+    let mut runs = vec![];
+    for toolchain in toolchains {
+        let cargo = match toolchain {
+            Some(name) => Cargo::find(&Toolchain::Named(name.clone()), true, cargo_path)
+                .with_context(|| {
+                    format!("the `{name}` toolchain from the matrix isn't installed")
+                })?,
+            None => opts.cargo.clone(),
+        };
+
+        for target in &targets {
+            let mut opts = opts.clone();
+            opts.cargo = cargo.clone();
+            if let Some(target) = target {
+                opts.cargo_args.push(String::from("--target"));
+                opts.cargo_args.push(target.to_string());
+            }
+
+            // only the parts that differ between the cells are in the label
+            let toolchain = toolchain.filter(|_| matrix.toolchains.len() > 1 || target.is_none());
+            let target = target.filter(|_| matrix.targets.len() > 1 || toolchain.is_none());
+            let label = toolchain
+                .cloned()
+                .into_iter()
+                .chain(target.map(|target| short_name(target)))
+                .collect::<Vec<_>>()
+                .join("/");
+            runs.push((label, opts));
+        }
+    }
+
+    // cargo locks the target directory, so the cells can only run at the same time in their own
+    let parallel = opts.separate_target_dir && !opts.dry_run;
+
+    merge_runs(
+        command,
+        runs,
+        "",
+        parallel,
+        render_options,
+        first,
+        |labels| format!("only on {labels}", labels = labels.join(", ")),
+    )
+}
+
+// runs each of the labelled options, identical diagnostics are merged and tagged with the
+// labels of the runs that produced them, unless every run did. `prefix` is shown before the
+// label in the progress
+fn merge_runs(
+    command: &Command<'_>,
+    runs: Vec<(String, Options)>,
+    prefix: &str,
+    parallel: bool,
+    render_options: &RenderOptions,
+    first: bool,
+    tag: impl Fn(&[String]) -> String,
+) -> anyhow::Result<Output> {
+    let run = |label: &str, opts: Options| {
+        eprintln!("checking {prefix}{label}");
+        anyhow::Ok(
+            command
+                .clone()
+                .build_command(opts)?
+                .map(|process| read_reasons(process, render_options, first, None)),
+        )
+    };
+
+    let mut results = vec![];
+    if parallel {
+        std::thread::scope(|scope| {
+            // NOTE the progress bars would draw over each other
+            let handles = runs
+                .iter()
+                .map(|(label, opts)| {
+                    let opts = Options {
+                        progress: false,
+                        ..opts.clone()
+                    };
+                    scope.spawn(|| run(label, opts))
+                })
+                .collect::<Vec<_>>();
+            for (handle, (label, _)) in handles.into_iter().zip(&runs) {
+                let output = handle.join().expect("the check shouldn't panic")?;
+                results.extend(output.map(|output| (label.clone(), output)));
+            }
+            anyhow::Ok(())
+        })?;
+    } else {
+        for (label, opts) in runs {
+            let Some(output) = run(&label, opts)? else {
+                continue;
+            };
+            let has_error = output.reasons.iter().any(|reason| {
+                matches!(reason, Reason::CompilerMessage { message } if matches!(message.level, Level::Error))
+            });
+            results.push((label, output));
+            if (first && has_error) || ccs::interrupted() {
+                break;
+            }
+        }
+    }
+
+    // NOTE with --first, or a dry run, not every run produced results
+    let total = results.len();
+    let (mut reasons, mut stderr) = (Merged::default(), Merged::default());
+    let mut summary = None;
+    for (label, output) in results {
+        for reason in output.reasons {
+            if let Reason::CompilerMessage { message } = reason {
+                reasons.insert(message, &label);
+            }
+        }
+        for message in output.stderr {
+            stderr.insert(message, &label);
+        }
+        summary = summary.or(output.summary);
+    }
+
+    Ok(Output {
+        reasons: reasons
+            .tagged(total, &tag)
+            .map(|message| Reason::CompilerMessage { message })
+            .collect(),
+        stderr: stderr.tagged(total, &tag).collect(),
+        summary,
+    })
+}
+
+// messages in the order they were first seen, with the labels of the runs that produced them
+#[derive(Default)]
+struct Merged {
+    messages: Vec<(Message, Vec<String>)>,
+    seen: HashMap<MessageKey, usize>,
+}
+
+impl Merged {
+    fn insert(&mut self, message: Message, label: &str) {
+        match self.seen.entry(message_key(&message)) {
+            Entry::Occupied(entry) => {
+                // a run can repeat a diagnostic, e.g. for the lib and its tests
+                let labels = &mut self.messages[*entry.get()].1;
+                if labels.last().map(String::as_str) != Some(label) {
+                    labels.push(label.to_string());
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(self.messages.len());
+                self.messages.push((message, vec![label.to_string()]));
+            }
+        }
+    }
+
+    // the ones that every run produced aren't tagged
+    fn tagged<'a>(
+        self,
+        total: usize,
+        tag: &'a dyn Fn(&[String]) -> String,
+    ) -> impl Iterator<Item = Message> + 'a {
+        self.messages.into_iter().map(move |(mut message, labels)| {
+            if labels.len() < total {
+                message.tags.push(tag(&labels));
+            }
+            message
+        })
+    }
+}
+
+type MessageKey = (&'static str, Option<String>, String, Vec<String>);
+
+// diagnostics are the same if they'd be shown the same way
+fn message_key(message: &Message) -> MessageKey {
+    (
+        message.level.as_str(),
+        message.code.as_ref().map(|code| code.code.clone()),
+        message.message.clone(),
+        message
+            .spans
+            .iter()
+            .map(|span| {
+                format!(
+                    "{location}-{line}:{col}",
+                    location = span.location(),
+                    line = span.line_end,
+                    col = span.column_end
+                )
+            })
+            .collect(),
+    )
+}
+
+// writes the filtered messages as json, returning how many errors were written
+fn emit_cargo_json(
+    mut process: Process,
+    render_options: &RenderOptions,
+    first: bool,
+    max: Option<usize>,
+) -> anyhow::Result<usize> {
+    use std::io::Write as _;
+    let mut out = std::io::stdout().lock();
+
+    let stdout = process.take_stdout();
+    let (mut shown, mut errors) = (0, 0);
+    for (mut reason, raw) in iter_raw_reasons(BufReader::new(stdout)) {
+        let Reason::CompilerMessage { message } = &mut reason else {
+            writeln!(out, "{raw}", raw = raw.get())?;
+            continue;
+        };
+
+        // these aren't shown when rendering either
+        if message.spans.is_empty() && message.children.is_empty() {
+            continue;
+        }
+
+        render_options.remap_level(message);
+        if render_options.is_ignored(message) || message.is_hidden(render_options) {
+            continue;
+        }
+
+        // only remapped messages are changed, so the editor shows the same level
+        if let Some(original) = message.original_level {
+            let mut value = serde_json::from_str::<serde_json::Value>(raw.get())?;
+            let level = message.level.as_str();
+            value["message"]["level"] = level.into();
+            // `rendered` starts with the level too, e.g. `warning[E0599]: ..`
+            if let Some(rendered) = value["message"]["rendered"].as_str() {
+                if let Some(rest) = rendered.strip_prefix(original.as_str()) {
+                    value["message"]["rendered"] = format!("{level}{rest}").into();
+                }
+            }
+            writeln!(out, "{value}")?;
+        } else {
+            writeln!(out, "{raw}", raw = raw.get())?;
+        }
+        out.flush()?;
+
+        let is_error = matches!(message.level, Level::Error);
+        errors += is_error as usize;
+        shown += 1;
+
+        if (first && is_error) || max.is_some_and(|max| shown >= max) {
+            process.kill();
+            break;
+        }
+    }
+
+    // the json is for other tools, so what cargo wrote to stderr stays on stderr
+    let finished = process.finish();
+    let exit_error = finished.exit_error(errors > 0);
+    for message in finished
+        .messages
+        .iter()
+        .chain(&finished.summary)
+        .chain(&exit_error)
+    {
+        errors += matches!(message.level, Level::Error) as usize;
+        eprintln!(
+            "{level}: {message}",
+            level = message.level.as_str(),
+            message = message.message
+        );
+    }
+
+    Ok(errors)
+}
+
+// NOTE without a locale this is the C locale, which isn't UTF-8 either
+#[cfg(not(windows))]
+fn is_non_utf8_locale() -> bool {
+    if std::env::var_os("WT_SESSION").is_some() {
+        return false;
+    }
+
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok().filter(|s| !s.is_empty()))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    !locale.contains("utf-8") && !locale.contains("utf8")
+}
+
+// windows doesn't set a locale, Windows Terminal and the UTF-8 code page show the glyphs fine
+#[cfg(windows)]
+fn is_non_utf8_locale() -> bool {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleOutputCP() -> u32;
     }
+    const CP_UTF8: u32 = 65001;
+
+    if std::env::var_os("WT_SESSION").is_some() {
+        return false;
+    }
+    // SAFETY: this only reads the console's code page, it's 0 without a console
+    unsafe { GetConsoleOutputCP() != CP_UTF8 }
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut args = Args::parse();
 
     if args.all_features && args.no_features {
         eprintln!("Error: `all-features` and `no-default-features` are exclusive");
@@ -68,8 +553,15 @@ fn main() -> anyhow::Result<()> {
     }
 
     let mut theme = Theme::default();
+    let mut theme_name = match &args.subcommand {
+        Some(SubCommand::ThemePreview { name: Some(name) }) => Some(name.clone()),
+        _ => args.theme.take(),
+    };
 
     let mut continuation = Some(Config::CONTINUATION);
+    let mut cargo_path = None;
+    let mut templates = Templates::default();
+    let mut matrix = Matrix::default();
 
     if !args.ignore_config {
         if let Some(path) = Config::get_config_path() {
@@ -95,26 +587,108 @@ fn main() -> anyhow::Result<()> {
             };
 
             continuation = config.continuation;
+            cargo_path = config.options.cargo;
+            if let Some(wrapper) = config.options.wrapper {
+                args.wrapper.get_or_insert(wrapper);
+            }
+            args.separate_target_dir ^= config.options.separate_target_dir;
+            if let Some(timeout) = config.options.timeout {
+                args.timeout.get_or_insert(timeout);
+            }
+            // the configuration comes first, so the command line can add to it
+            config.options.cargo_args.append(&mut args.cargo_args);
+            args.cargo_args = config.options.cargo_args;
+            config.options.lint_args.append(&mut args.lint_args);
+            args.lint_args = config.options.lint_args;
+            templates = config.templates;
+            matrix = config.matrix;
 
             args.warning.append(&mut config.lints.warn);
             args.allow.append(&mut config.lints.allow);
             args.deny.append(&mut config.lints.deny);
+            args.filter.append(&mut config.filters);
+            args.only.append(&mut config.only);
+            args.remap.append(&mut config.remap);
 
-            // args.tool = config.tool;
+            args.tool.get_or_insert(config.tool);
 
             args.nightly ^= config.options.nightly;
+            if let (false, Some(name)) = (args.nightly, config.options.toolchain) {
+                args.toolchain.get_or_insert(name);
+            }
             args.explain ^= config.options.explain;
+            args.explain_lints ^= config.options.explain_lints;
             args.new_line ^= config.options.new_line;
+            args.ascii ^= config.options.ascii;
+            args.format.get_or_insert(config.options.format);
             args.include_notes ^= config.options.include_notes;
 
             args.delimiter.get_or_insert(config.options.delimiter);
+            if args.sort.is_empty() {
+                args.sort = config.options.sort;
+            }
+            if !ColorChoice::is_set_by_env() {
+                args.color.get_or_insert(config.options.color);
+            }
+            args.hyperlinks.get_or_insert(config.options.hyperlinks);
+            if let Some(editor_url) = config.options.editor_url {
+                args.editor_url.get_or_insert(editor_url);
+            }
 
-            theme = config.theme;
+            theme_name = theme_name.or(config.options.theme);
+            if theme_name.is_none() {
+                let map = config.theme.into_iter().collect();
+                let (loaded, report) = Theme::load(map).expect("theme should always load");
+                print_theme_report("the configuration file", &report);
+                theme = loaded;
+            }
         }
     }
 
-    if args.nightly && !is_nightly_available() {
-        eprintln!("rust nightly isn't installed");
+    if let Some(name) = theme_name {
+        theme = load_theme(&name);
+    }
+
+    let color = args.color.unwrap_or_default().into();
+    // cargo writes its status lines to stderr, so that's the one that is checked
+    let cargo_color = match color {
+        anstream::ColorChoice::Auto => AutoStream::choice(&std::io::stderr()),
+        choice => choice,
+    } != anstream::ColorChoice::Never;
+
+    let ascii = args.ascii || is_non_utf8_locale();
+    if ascii {
+        continuation = continuation.map(|c| {
+            if c.is_ascii() {
+                c
+            } else {
+                Config::ASCII_CONTINUATION
+            }
+        });
+    }
+
+    match &args.subcommand {
+        Some(SubCommand::ThemeList) => {
+            print_themes();
+            return Ok(());
+        }
+        Some(SubCommand::ThemePreview { .. }) => {
+            let mut out = AutoStream::new(std::io::stdout(), color).lock();
+            let render_options = RenderOptions {
+                ascii,
+                templates: templates.clone(),
+                ..RenderOptions::default()
+            };
+            theme.preview(render_options, &continuation, &mut out)?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // the tool can come from the configuration, so this is checked after it's loaded
+    let tool = args.tool.unwrap_or_default();
+    if tool != Tool::Clippy && (args.annoying || args.more_annoying) {
+        eprintln!("Error: -y / -Y requires `--tool clippy`");
         std::process::exit(1)
     }
 
@@ -122,21 +696,69 @@ fn main() -> anyhow::Result<()> {
         try_find_manifest(path)?;
     }
 
-    let mut toolchain = args
-        .nightly
-        .then_some(Toolchain::Nightly)
-        .unwrap_or_default();
+    // `--toolchain` wins over `--nightly`, which wins over the project's toolchain file
+    // a toolchain file is only a preference, the others need that toolchain
+    let mut required = args.toolchain.is_some() || args.nightly;
+    let mut toolchain = match args.toolchain.take() {
+        Some(name) => Toolchain::Named(name),
+        None if args.nightly => Toolchain::nightly(),
+        None => {
+            Toolchain::from_toolchain_file(&link_root(args.path.as_deref())).unwrap_or_default()
+        }
+    };
 
-    let command = if args.nightly && args.annoying {
-        toolchain = Toolchain::Nightly;
+    let command = if toolchain.is_nightly() && args.annoying {
         Command::annoying()
     } else if args.more_annoying {
-        toolchain = Toolchain::Nightly;
+        if !toolchain.is_nightly() {
+            toolchain = Toolchain::nightly();
+        }
+        required = true;
         Command::more_annoying()
     } else {
         Command::default_lints()
     };
 
+    let Some(cargo) = Cargo::find(&toolchain, required, cargo_path.as_deref()) else {
+        match toolchain {
+            Toolchain::Named(..) if required => {
+                eprintln!("the `{toolchain}` toolchain isn't installed")
+            }
+            _ => eprintln!(
+                "cannot find cargo, set $CARGO or `cargo` in the configuration file, or add it to PATH"
+            ),
+        }
+        std::process::exit(1)
+    };
+
+    if !required && cargo.source != CargoSource::Rustup(toolchain.clone()) {
+        if let Toolchain::Named(..) = toolchain {
+            eprintln!(
+                "the `{toolchain}` toolchain from the project's toolchain file isn't available \
+                through rustup, using cargo from {source}",
+                source = cargo.source
+            );
+        }
+    }
+
+    // without rustup, nightly is only refused if the cargo that was found isn't nightly
+    if required && toolchain.is_nightly() && !cargo.is_nightly() {
+        eprintln!(
+            "a nightly cargo is needed, but `{path}` (from {source}) is {version}",
+            path = cargo.path.display(),
+            source = cargo.source,
+            version = cargo.version().as_deref().unwrap_or("unknown"),
+        );
+        std::process::exit(1)
+    }
+
+    if let Some(SubCommand::Explain { lint }) = &args.subcommand {
+        let text = ccs::explain(&cargo.path, lint)?;
+        let mut out = AutoStream::new(std::io::stdout(), color).lock();
+        ccs::render_explanation(&text, &theme, &mut out)?;
+        return Ok(());
+    }
+
     let mut target = match (args.tests, args.examples, args.all_targets) {
         (.., true) => Target::All,
         (true, _, false) => Target::Test,
@@ -156,15 +778,27 @@ fn main() -> anyhow::Result<()> {
     };
 
     let mut render_options = RenderOptions {
-        render: args
-            .explain
-            .then_some(RenderStyle::Full)
-            .unwrap_or_default(),
+        render: if args.explain {
+            RenderStyle::Full
+        } else {
+            RenderStyle::default()
+        },
+
+        include_notes: if args.include_notes {
+            IncludeNotes::Yes
+        } else {
+            IncludeNotes::default()
+        },
+
+        hyperlinks: Hyperlinks::new(
+            args.hyperlinks.unwrap_or_default(),
+            link_root(args.path.as_deref()),
+            args.editor_url.take(),
+        ),
 
-        include_notes: args
-            .include_notes
-            .then_some(IncludeNotes::Yes)
-            .unwrap_or_default(),
+        ascii,
+        templates,
+        format: args.format.unwrap_or_default(),
 
         ..RenderOptions::default()
     };
@@ -175,6 +809,23 @@ fn main() -> anyhow::Result<()> {
             ccs::Filter::AllErrors => render_options.without_errors(),
             ccs::Filter::Error(lint) => render_options.without_error(lint),
             ccs::Filter::Warning(lint) => render_options.without_warning(lint),
+            ccs::Filter::Expr(expr) => render_options.without_matching(expr),
+        }
+    }
+
+    for only in std::mem::take(&mut args.only) {
+        render_options = render_options.only(only);
+    }
+
+    for lint in std::mem::take(&mut args.only_lint) {
+        render_options = render_options.only_lint(lint);
+    }
+
+    // rules from the command line come first, so they take precedence over the configuration
+    let profile = ccs::cargo_profile(&args.cargo_args);
+    for remap in std::mem::take(&mut args.remap) {
+        if remap.applies_to(profile) {
+            render_options = render_options.remap(remap);
         }
     }
 
@@ -183,12 +834,12 @@ fn main() -> anyhow::Result<()> {
         warning,
         deny,
         dry_run,
-        tool,
         ..
     } = args;
 
     let opts = Options {
-        toolchain,
+        cargo: cargo.clone(),
+        wrapper: args.wrapper.unwrap_or_default(),
         extra: Extra {
             allow,
             warning,
@@ -199,32 +850,161 @@ fn main() -> anyhow::Result<()> {
         features,
         dry_run,
         tool,
+        cargo_args: args.cargo_args,
+        separate_target_dir: args.separate_target_dir,
+        fresh: args.fresh,
+        timeout: args.timeout.map(Duration::from_secs),
+        color: cargo_color,
+        progress: true,
+        lint_args: args
+            .lint_args
+            .iter()
+            .flat_map(|s| s.split_whitespace())
+            .map(String::from)
+            .collect(),
+    };
+
+    if let Some(SubCommand::Lsp) = args.subcommand {
+        ccs::run_lsp(command, opts, render_options)?;
+        if ccs::interrupted() {
+            std::process::exit(130)
+        }
+        return Ok(());
+    }
+
+    if matches!(args.emit, Emit::CargoJson) {
+        if args.powerset.is_some() || args.matrix {
+            eprintln!("Error: `--emit cargo-json` can't be used with `--matrix`, `--each-feature` or `--feature-powerset`");
+            std::process::exit(1)
+        }
+
+        let Some(process) = command.build_command(opts)? else {
+            return Ok(());
+        };
+        let errors = emit_cargo_json(process, &render_options, args.first, args.max)?;
+        if ccs::interrupted() {
+            std::process::exit(130)
+        }
+        if errors > 0 {
+            std::process::exit(1)
+        }
+        return Ok(());
+    }
+
+    let Output {
+        mut reasons,
+        stderr,
+        summary,
+    } = match args.powerset {
+        _ if args.matrix => check_matrix(
+            &command,
+            &opts,
+            &matrix,
+            cargo_path.as_deref(),
+            &render_options,
+            args.first,
+        )?,
+        Some(powerset) => check_feature_sets(
+            &command,
+            &opts,
+            powerset,
+            &args.exclude_features,
+            &render_options,
+            args.first,
+        )?,
+        None => {
+            let Some(process) = command.build_command(opts)? else {
+                return Ok(());
+            };
+            // when sorting everything has to be read before the first n can be picked
+            let limit = args.max.filter(|_| args.sort.is_empty());
+            read_reasons(process, &render_options, args.first, limit)
+        }
+    };
+
+    // ctrl-c stops everything, there's nothing useful left to show
+    if ccs::interrupted() {
+        std::process::exit(130)
+    }
+
+    if !args.sort.is_empty() {
+        reasons.sort_by(|left, right| match (left, right) {
+            (
+                Reason::CompilerMessage { message: left },
+                Reason::CompilerMessage { message: right },
+            ) => SortBy::compare_all(&args.sort, left, right),
+            _ => std::cmp::Ordering::Equal,
+        });
+    }
+
+    if let Some(max) = args.max {
+        reasons.truncate(max);
+    }
+
+    let is_error = |reason: &Reason| {
+        matches!(
+            reason,
+            Reason::CompilerMessage { message } if matches!(message.level, Level::Error)
+        )
     };
 
-    let reasons = gather_reasons(BufReader::new(command.build_command(opts)?));
-    let mut out = AutoStream::new(std::io::stdout(), anstream::ColorChoice::Auto).lock();
+    // cargo's own messages come last, its summary is only useful if no errors were shown
+    let has_error = reasons.iter().any(is_error);
+    reasons.extend(
+        stderr
+            .into_iter()
+            .chain(summary.filter(|_| !has_error))
+            .map(|message| Reason::CompilerMessage { message }),
+    );
+
+    let mut out = AutoStream::new(std::io::stdout(), color).lock();
+    let mut errors = 0;
+
+    // the line format is read by other tools, so it has to be exactly one line per diagnostic
+    let is_line = matches!(render_options.format, Format::Line);
+
+    let cargo = (args.explain_lints && !is_line).then_some(&cargo.path);
+    let mut explained = HashSet::new();
 
     reasons
         .into_iter()
-        .filter(|reason| {
-            if let Reason::CompilerMessage { message } = &reason {
-                !render_options.is_ignored(message.level, message.code.as_ref().map(|c| &*c.code))
-            } else {
-                true
-            }
-        })
         .enumerate()
         .try_for_each(|(i, reason)| {
             use std::io::Write as _;
-            if i > 0 {
+            if i > 0 && !is_line {
                 if let Some(delim) = &args.delimiter.as_ref().filter(|c| !c.is_empty()) {
                     writeln!(out, "{delim}")?;
                 } else if args.new_line {
                     writeln!(out)?;
                 }
             }
+
+            if is_error(&reason) {
+                errors += 1;
+            }
+
             reason.render(&render_options, &theme, &continuation, &mut out)?;
+
+            if let (Some(cargo), Reason::CompilerMessage { message }) = (&cargo, &reason) {
+                let code = message.code.as_ref().map(|c| &c.code);
+                if let Some(code) = code.filter(|&code| explained.insert(code.clone())) {
+                    let summary = ccs::explain(cargo, code)
+                        .ok()
+                        .and_then(|text| ccs::explain_summary(&text));
+                    if let Some(summary) = summary {
+                        write!(out, "   ")?;
+                        ccs::render_explanation(&summary, &theme, &mut out)?;
+                    }
+                }
+            }
+
             std::io::Result::Ok(())
-        })
-        .map_err(Into::into)
+        })?;
+
+    // the exit code reflects what was shown, so remapped levels count and filtered ones don't
+    if errors > 0 {
+        std::process::exit(1)
+    }
+
+    Ok(())
 }
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use crate::Toolchain;
+
+/// A cargo binary, and where it was found
+#[derive(Clone, Debug)]
+pub struct Cargo {
+    pub path: PathBuf,
+    pub source: CargoSource,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CargoSource {
+    Rustup(Toolchain),
+    Env,
+    Config,
+    Path,
+}
+
+impl std::fmt::Display for CargoSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rustup(toolchain) => write!(f, "rustup (the `{toolchain}` toolchain)"),
+            Self::Env => f.write_str("$CARGO"),
+            Self::Config => f.write_str("the configuration file"),
+            Self::Path => f.write_str("PATH"),
+        }
+    }
+}
+
+impl Cargo {
+    /// Finds cargo for a toolchain, `configured` is the path from the configuration file
+    ///
+    /// A `required` toolchain needs rustup, e.g. one from `--toolchain`. Otherwise this tries
+    /// `$CARGO`, then `configured`, then rustup's default and finally `cargo` on `PATH`
+    ///
+    /// NOTE without rustup, nightly can still be used if one of the others is a nightly cargo
+    pub fn find(toolchain: &Toolchain, required: bool, configured: Option<&Path>) -> Option<Self> {
+        if let Toolchain::Named(..) = toolchain {
+            if let Some(path) = rustup_which(toolchain) {
+                return Some(Self::new(path, CargoSource::Rustup(toolchain.clone())));
+            }
+            if required && !toolchain.is_nightly() {
+                return None;
+            }
+        }
+
+        let env = std::env::var_os("CARGO")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from);
+        if let Some(path) = env.filter(|path| path.is_file()) {
+            return Some(Self::new(path, CargoSource::Env));
+        }
+
+        if let Some(path) = configured.filter(|path| path.is_file()) {
+            return Some(Self::new(path.to_path_buf(), CargoSource::Config));
+        }
+
+        if let Some(path) = rustup_which(&Toolchain::Default) {
+            return Some(Self::new(path, CargoSource::Rustup(Toolchain::Default)));
+        }
+
+        let name = format!("cargo{suffix}", suffix = std::env::consts::EXE_SUFFIX);
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(&name))
+            .find(|path| path.is_file())
+            .map(|path| Self::new(path, CargoSource::Path))
+    }
+
+    const fn new(path: PathBuf, source: CargoSource) -> Self {
+        Self { path, source }
+    }
+
+    /// The output of `cargo --version`
+    pub fn version(&self) -> Option<String> {
+        let output = std::process::Command::new(&self.path)
+            .arg("--version")
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn is_nightly(&self) -> bool {
+        if let CargoSource::Rustup(toolchain) = &self.source {
+            if toolchain.is_nightly() {
+                return true;
+            }
+        }
+
+        // e.g. `cargo 1.80.0-nightly (...)`, local builds are `-dev`
+        self.version()
+            .is_some_and(|version| version.contains("-nightly") || version.contains("-dev"))
+    }
+}
+
+fn rustup_which(toolchain: &Toolchain) -> Option<PathBuf> {
+    let mut cmd = std::process::Command::new("rustup");
+    // a missing toolchain should be an error, not a download
+    cmd.env("RUSTUP_AUTO_INSTALL", "0");
+    if let Some(toolchain) = toolchain.as_arg() {
+        cmd.arg(toolchain);
+    }
+
+    let output = cmd.args(["which", "cargo"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let output = String::from_utf8(output.stdout).ok()?;
+    let output = output.trim_end();
+    (!output.is_empty()).then(|| PathBuf::from(output))
+}
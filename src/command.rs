@@ -1,10 +1,16 @@
-use std::{borrow::Cow, ffi::OsStr, io::Read, path::PathBuf, process::Stdio};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
 
-use anyhow::Context;
+use anyhow::Context as _;
 
-use crate::Tool;
+use crate::{Cargo, CargoSource, Metadata, Process, Tool};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Command<'a> {
     pub args: Vec<Cow<'a, OsStr>>,
 }
@@ -42,40 +48,60 @@ impl<'a> Command<'a> {
         Self { args: vec![] }
     }
 
-    pub fn build_command(self, opts: Options) -> anyhow::Result<impl Read> {
+    /// Spawns the command, its `stdout` is piped so the json messages can be read from it
+    ///
+    /// For a dry run the command is printed instead, and this returns `None`
+    pub fn build_command(self, opts: Options) -> anyhow::Result<Option<Process>> {
         let Options {
             extra,
             path,
-            toolchain,
+            cargo,
+            wrapper,
             target,
             features,
             dry_run,
             tool,
+            cargo_args,
+            lint_args,
+            separate_target_dir,
+            fresh,
+            timeout,
+            color,
+            progress,
         } = opts;
 
-        let cargo = crate::find_cargo(toolchain).with_context(|| "cannot find cargo via rustup")?;
-        let mut cmd = std::process::Command::new(&cargo);
-        cmd.stdout(Stdio::piped());
+        // wrappers like `cross` take the same arguments as cargo, and find their own toolchain
+        let mut cmd = match wrapper.split_first() {
+            Some((program, args)) => {
+                let mut cmd = std::process::Command::new(program);
+                cmd.args(args);
+                cmd
+            }
+            None => std::process::Command::new(&cargo.path),
+        };
+        // NOTE a toolchain's cargo still runs `rustc` and `clippy-driver` through the rustup proxies,
+        // which would pick the default toolchain without this
+        if let CargoSource::Rustup(Toolchain::Named(name)) = &cargo.source {
+            cmd.env("RUSTUP_TOOLCHAIN", name);
+        }
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
-        cmd.args([Self::as_command(tool), "--message-format=json"]);
-        if let Some(path) = path {
+        cmd.args(Self::as_command(tool));
+        cmd.arg("--message-format=json");
+        if let Some(path) = &path {
             cmd.arg("--manifest-path");
             cmd.arg(path);
         }
 
-        match target {
-            Target::All => {
-                cmd.arg("--all-targets");
-            }
-            Target::Example => {
-                cmd.arg("--examples");
-            }
-            Target::Test => {
-                cmd.arg("--tests");
-            }
+        // NOTE these are kept so they're hashed like the same flags passed after `--`
+        let mut selection = vec![];
+        match &target {
+            Target::All => selection.push("--all-targets".to_string()),
+            Target::Example => selection.push("--examples".to_string()),
+            Target::Test => selection.push("--tests".to_string()),
             Target::Specific(targets) => {
                 for target in targets {
-                    cmd.arg("--target").arg(target);
+                    selection.extend(["--target".to_string(), target.clone()]);
                 }
             }
 
@@ -83,62 +109,189 @@ impl<'a> Command<'a> {
         }
 
         match features {
-            Features::All => {
-                cmd.arg("--all-features");
-            }
-            Features::None => {
-                cmd.arg("--no-default-features");
-            }
+            Features::All => selection.push("--all-features".to_string()),
+            Features::None => selection.push("--no-default-features".to_string()),
             Features::Specific(features) => {
                 for feature in features {
-                    cmd.arg("--features").arg(feature);
+                    selection.extend(["--features".to_string(), feature]);
+                }
+            }
+            Features::Exactly(features) => {
+                selection.push("--no-default-features".to_string());
+                for feature in features {
+                    selection.extend(["--features".to_string(), feature]);
                 }
             }
             Features::Default => {}
         }
 
-        cmd.arg("--");
+        cmd.args(&selection);
+        cmd.args(&cargo_args);
+
+        let lints = self
+            .args
+            .iter()
+            .map(|s| &**s)
+            .chain(
+                extra
+                    .as_flags()
+                    .flat_map(|(key, val)| [OsStr::new(key), OsStr::new(val)]),
+            )
+            .chain(lint_args.iter().map(OsStr::new))
+            .collect::<Vec<_>>();
 
-        if !self.args.is_empty() {
-            cmd.args(&self.args);
+        let metadata = (separate_target_dir || fresh)
+            .then(|| Metadata::load(&cargo, path.as_deref()))
+            .transpose()?;
+
+        // NOTE rust-analyzer checks with its own flags, so sharing `target` means both keep
+        // rebuilding. each toolchain, set of lints and build flags gets its own directory
+        let mut target_dir = find_target_dir(&cargo_args).map(PathBuf::from);
+        if let (true, None, Some(metadata)) = (separate_target_dir, &target_dir, &metadata) {
+            let toolchain = match &cargo.source {
+                CargoSource::Rustup(Toolchain::Named(name)) => name.replace(
+                    |c: char| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '.'),
+                    "_",
+                ),
+                _ => String::from("default"),
+            };
+            let hash = hash_flags(
+                Self::as_command(tool)
+                    .iter()
+                    .map(OsStr::new)
+                    .chain(lints.iter().copied())
+                    .chain(
+                        find_flags(&selection, BUILD_FLAGS)
+                            .into_iter()
+                            .chain(find_flags(&cargo_args, BUILD_FLAGS))
+                            .map(OsStr::new),
+                    ),
+            );
+            let dir = metadata
+                .target_directory
+                .join(env!("CARGO_PKG_NAME"))
+                .join(format!("{toolchain}-{hash:016x}"));
+            cmd.arg("--target-dir").arg(&dir);
+            target_dir = Some(dir);
         }
 
-        for (key, val) in extra.as_flags() {
-            cmd.arg(key);
-            cmd.arg(val);
+        // cleaning only the workspace members makes cargo lint them again, without rebuilding dependencies
+        let clean = match (fresh, &metadata) {
+            (true, Some(metadata)) => {
+                let mut clean = std::process::Command::new(&cargo.path);
+                if let CargoSource::Rustup(Toolchain::Named(name)) = &cargo.source {
+                    clean.env("RUSTUP_TOOLCHAIN", name);
+                }
+                clean.arg("clean");
+                if let Some(path) = &path {
+                    clean.arg("--manifest-path").arg(path);
+                }
+                if let Some(dir) = &target_dir {
+                    clean.arg("--target-dir").arg(dir);
+                }
+                if let Target::Specific(targets) = &target {
+                    for target in targets {
+                        clean.arg("--target").arg(target);
+                    }
+                }
+                // NOTE each profile has its own directory, and only the one being used is cleaned
+                clean.args(find_flags(&cargo_args, &["--release", "-r", "--profile"]));
+                for package in &metadata.packages {
+                    clean.arg("-p").arg(&package.name);
+                }
+                Some(clean)
+            }
+            _ => None,
+        };
+
+        // NOTE only clippy passes arguments through to the compiler, the other tools get the lints
+        // from the flags instead. changing these causes cargo to rebuild everything
+        let flags = match tool {
+            Tool::Clippy => None,
+            Tool::Doc => Some(("RUSTDOCFLAGS", "build.rustdocflags")),
+            Tool::Check | Tool::Build | Tool::Test => Some(("RUSTFLAGS", "build.rustflags")),
+        };
+
+        match flags {
+            Some(..) if lints.is_empty() => {}
+            Some((key, config)) => {
+                // NOTE setting the environment replaces the flags from cargo's configuration, so
+                // it's only extended if it's already set. the encoded one takes precedence
+                let encoded = format!("CARGO_ENCODED_{key}");
+                let env = [(&*encoded, "\x1f"), (key, " ")]
+                    .into_iter()
+                    .find_map(|(var, separator)| Some((var, separator, std::env::var_os(var)?)));
+
+                if let Some((var, separator, mut flags)) = env {
+                    eprintln!("warning: the lints are added to `{var}`, which overrides cargo's configuration");
+                    for lint in lints {
+                        if !flags.is_empty() {
+                            flags.push(separator);
+                        }
+                        flags.push(lint);
+                    }
+                    cmd.env(var, flags);
+                } else {
+                    // `--config` arrays are merged with the ones from the configuration files
+                    let lints = lints
+                        .iter()
+                        .map(|lint| toml::Value::String(lint.to_string_lossy().into_owned()))
+                        .collect();
+                    let lints = toml::Value::Array(lints);
+                    cmd.arg("--config").arg(format!("{config}={lints}"));
+                }
+            }
+            None => {
+                cmd.arg("--");
+                cmd.args(lints);
+            }
         }
 
         if dry_run {
-            let args =
-                cmd.get_args()
-                    .map(OsStr::to_string_lossy)
-                    .fold(String::new(), |mut a, c| {
-                        if !a.is_empty() {
-                            a.push(' ');
-                        }
-                        a.push_str(&c);
-                        a
-                    });
-            let name = cmd.get_program().to_string_lossy();
-            println!("{name} {args}");
-            std::process::exit(0);
+            match wrapper.first() {
+                Some(wrapper) => eprintln!("using the wrapper `{wrapper}`"),
+                None => eprintln!("using cargo from {source}", source = cargo.source),
+            }
+            if let Some(clean) = &clean {
+                println!("{clean}", clean = display_command(clean));
+            }
+            println!("{cmd}", cmd = display_command(&cmd));
+            return Ok(None);
         }
 
-        let child = cmd.spawn()?;
-        let stderr = child.stdout.expect("stdout attached to the child process");
+        // NOTE cargo's stderr is piped, so it can't see the terminal for itself
+        if std::env::var_os("CARGO_TERM_COLOR").is_none() {
+            cmd.env("CARGO_TERM_COLOR", if color { "always" } else { "never" });
+        }
+        let width = progress
+            .then(terminal_width)
+            .flatten()
+            .filter(|_| std::env::var_os("CARGO_TERM_PROGRESS_WHEN").is_none());
+        if let Some(width) = width {
+            cmd.env("CARGO_TERM_PROGRESS_WHEN", "always");
+            cmd.env("CARGO_TERM_PROGRESS_WIDTH", width.to_string());
+        }
 
-        Ok(stderr)
+        if let Some(mut clean) = clean {
+            let status = clean.status().with_context(|| "cannot run cargo clean")?;
+            anyhow::ensure!(status.success(), "cargo clean failed: {status}");
+        }
+
+        Process::spawn(cmd, timeout).map(Some)
     }
 
-    const fn as_command(tool: Tool) -> &'static str {
+    const fn as_command(tool: Tool) -> &'static [&'static str] {
         match tool {
-            Tool::Clippy => "clippy",
-            Tool::Check => "check",
+            Tool::Clippy => &["clippy"],
+            Tool::Check => &["check"],
+            Tool::Build => &["build"],
+            Tool::Test => &["test", "--no-run"],
+            Tool::Doc => &["doc"],
         }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Extra {
     pub allow: Vec<String>,
     pub warning: Vec<String>,
@@ -171,33 +324,285 @@ pub enum Target {
 pub enum Features {
     All,
     Specific(Vec<String>),
+    /// Only these features, without the default ones
+    Exactly(Vec<String>),
     Default,
     None,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Options {
     pub extra: Extra,
     pub path: Option<PathBuf>,
-    pub toolchain: Toolchain,
+    pub cargo: Cargo,
+    /// A command used in place of cargo, e.g. `cross` or `cargo zigbuild`
+    pub wrapper: Vec<String>,
     pub target: Target,
     pub features: Features,
     pub dry_run: bool,
     pub tool: Tool,
+    /// Arguments for cargo, these go before the `--`
+    pub cargo_args: Vec<String>,
+    /// Raw arguments for the compiler, these go after the lints
+    pub lint_args: Vec<String>,
+    /// Use a directory in `target/ccs` rather than sharing `target` with rust-analyzer
+    pub separate_target_dir: bool,
+    /// Clean the workspace members first, so their lints aren't replayed from the cache
+    pub fresh: bool,
+    /// Kill cargo if it takes longer than this
+    pub timeout: Option<Duration>,
+    /// Whether cargo colors its status lines
+    pub color: bool,
+    /// Show cargo's progress bar, if stderr is a terminal
+    pub progress: bool,
+}
+
+fn display_command(cmd: &std::process::Command) -> String {
+    let mut out = String::new();
+    for (key, val) in cmd.get_envs() {
+        if let Some(val) = val {
+            out.push_str(&format!("{key}={val:?} ", key = key.to_string_lossy()));
+        }
+    }
+    // NOTE the program is quoted too, a wrapper from the configuration can have spaces in it
+    let program = std::iter::once(cmd.get_program());
+    for (i, arg) in program.chain(cmd.get_args()).enumerate() {
+        let arg = arg.to_string_lossy();
+        let plain = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_=:./,+@".contains(c));
+        if i > 0 {
+            out.push(' ');
+        }
+        // so it can be pasted into a shell, e.g. `--config build.rustflags=["-W", "unused"]`
+        if plain {
+            out.push_str(&arg);
+        } else {
+            out.push_str(&format!("'{arg}'", arg = arg.replace('\'', r"'\''")));
+        }
+    }
+    out
+}
+
+// the width of the terminal that stderr is written to
+#[cfg(unix)]
+fn terminal_width() -> Option<u16> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: this only writes to `size`, and fails if stderr isn't a terminal
+    let ok = unsafe { libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0).then_some(size.ws_col)
+}
+
+// NOTE without a width cargo doesn't draw the progress bar
+#[cfg(not(unix))]
+fn terminal_width() -> Option<u16> {
+    None
+}
+
+// `--target-dir dir` or `--target-dir=dir`
+fn find_target_dir(args: &[String]) -> Option<&str> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--target-dir" {
+            return iter.next().map(|s| &**s);
+        }
+        if let Some(dir) = arg.strip_prefix("--target-dir=") {
+            return Some(dir);
+        }
+    }
+    None
+}
+
+/// The profile cargo builds with for these arguments, `dev` unless `--release` or `--profile` is given
+pub fn cargo_profile(cargo_args: &[String]) -> &str {
+    let flags = find_flags(cargo_args, &["--release", "-r", "--profile"]);
+    match flags.last().copied() {
+        Some("--release" | "-r") => "release",
+        Some(flag) => flag.strip_prefix("--profile=").unwrap_or(flag),
+        None => "dev",
+    }
+}
+
+// the cargo arguments that change what's built, unlike e.g. `-p foo` or `--locked`
+const BUILD_FLAGS: &[&str] = &[
+    "--release",
+    "-r",
+    "--profile",
+    "--target",
+    "--features",
+    "-F",
+    "--all-features",
+    "--no-default-features",
+];
+
+// these flags from the arguments, with their values, e.g. `--profile x`, `--profile=x` or `-Fx`
+fn find_flags<'a>(args: &'a [String], flags: &[&str]) -> Vec<&'a str> {
+    let mut found = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (name, value) = match arg.split_once('=') {
+            _ if arg.starts_with("-F") && arg.len() > 2 => ("-F", true),
+            Some((name, _)) => (name, true),
+            None => (&**arg, false),
+        };
+        if !flags.contains(&name) {
+            continue;
+        }
+        found.push(&**arg);
+        if !value && matches!(name, "--profile" | "--target" | "--features" | "-F") {
+            found.extend(iter.next().map(|s| &**s));
+        }
+    }
+    found
+}
+
+// FNV-1a, the directory names have to stay the same between versions of ccs
+fn hash_flags<'a>(flags: impl Iterator<Item = &'a OsStr>) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for flag in flags {
+        for &byte in flag.as_encoded_bytes().iter().chain(&[0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
 }
 
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq)]
 pub enum Toolchain {
+    /// Whatever rustup picks, e.g. a directory override or the default toolchain
     #[default]
-    Stable,
-    Nightly,
+    Default,
+    /// A toolchain name, e.g. `beta`, `1.74.0` or `nightly-2024-05-01`
+    Named(String),
 }
 
 impl Toolchain {
-    pub const fn as_str(self) -> Option<&'static str> {
-        if matches!(self, Self::Nightly) {
-            return Some("+nightly");
+    pub fn nightly() -> Self {
+        Self::Named("nightly".to_string())
+    }
+
+    pub fn is_nightly(&self) -> bool {
+        matches!(self, Self::Named(name) if name.starts_with("nightly"))
+    }
+
+    /// The `+toolchain` argument for rustup
+    pub fn as_arg(&self) -> Option<String> {
+        match self {
+            Self::Default => None,
+            Self::Named(name) => Some(format!("+{name}")),
         }
-        None
+    }
+
+    /// Finds the channel in a `rust-toolchain.toml` (or `rust-toolchain`) file in `dir`, or above it
+    pub fn from_toolchain_file(dir: &Path) -> Option<Self> {
+        let data = dir.ancestors().find_map(|dir| {
+            ["rust-toolchain.toml", "rust-toolchain"]
+                .into_iter()
+                .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+        })?;
+
+        // the legacy file can just be the name of the toolchain
+        let channel = match toml::from_str::<toml::Table>(&data) {
+            Ok(table) => table
+                .get("toolchain")?
+                .get("channel")?
+                .as_str()?
+                .to_string(),
+            Err(..) => data.trim().to_string(),
+        };
+
+        (!channel.is_empty() && !channel.contains(char::is_whitespace))
+            .then_some(Self::Named(channel))
+    }
+}
+
+impl std::fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn find_flags_takes_values() {
+        let args = args(&[
+            "-p",
+            "foo",
+            "--profile",
+            "dev",
+            "--target=x86_64",
+            "-F",
+            "a",
+            "-Fb",
+            "--locked",
+            "-r",
+        ]);
+        assert_eq!(
+            find_flags(&args, BUILD_FLAGS),
+            [
+                "--profile",
+                "dev",
+                "--target=x86_64",
+                "-F",
+                "a",
+                "-Fb",
+                "-r"
+            ]
+        );
+        assert_eq!(
+            find_flags(&args, &["--release", "-r", "--profile"]),
+            ["--profile", "dev", "-r"]
+        );
+    }
+
+    #[test]
+    fn find_flags_missing_value() {
+        let args = args(&["--release", "--features"]);
+        assert_eq!(find_flags(&args, BUILD_FLAGS), ["--release", "--features"]);
+    }
+
+    #[test]
+    fn cargo_profile_from_args() {
+        assert_eq!(cargo_profile(&args(&["--locked"])), "dev");
+        assert_eq!(cargo_profile(&args(&["-r"])), "release");
+        assert_eq!(cargo_profile(&args(&["--release"])), "release");
+        assert_eq!(cargo_profile(&args(&["--profile", "bench"])), "bench");
+        assert_eq!(cargo_profile(&args(&["--profile=bench"])), "bench");
+    }
+
+    #[test]
+    fn hash_flags_is_stable() {
+        // NOTE changing these orphans every `target/ccs` directory made by an earlier version
+        assert_eq!(hash_flags(std::iter::empty()), 0xcbf2_9ce4_8422_2325);
+        // `ccs -F a --separate-target-dir`
+        let flags = ["clippy", "--features", "a"].map(OsStr::new);
+        assert_eq!(hash_flags(flags.into_iter()), 0xed03_d891_ba27_29a2);
+    }
+
+    #[test]
+    fn hash_flags_separates_flags() {
+        let joined = ["--features", "ab"].map(OsStr::new);
+        let split = ["--features", "a", "b"].map(OsStr::new);
+        assert_ne!(
+            hash_flags(joined.into_iter()),
+            hash_flags(split.into_iter())
+        );
     }
 }
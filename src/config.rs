@@ -1,9 +1,12 @@
 use std::{
     borrow::Cow,
+    collections::BTreeMap,
     path::{Path, PathBuf},
 };
 
-use crate::{args::Tool, Theme};
+use crate::{
+    args::Tool, ColorChoice, Filter, Format, LinkChoice, Only, Remap, SortBy, Templates, Theme,
+};
 
 #[derive(Default, serde::Serialize, serde::Deserialize)]
 pub struct Lints {
@@ -12,13 +15,86 @@ pub struct Lints {
     pub warn: Vec<String>,
 }
 
+/// The cells that `--matrix` checks, every toolchain is paired with every target
+#[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Matrix {
+    // e.g. `["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"]`
+    pub targets: Vec<String>,
+    // e.g. `["1.70", "stable"]`
+    pub toolchains: Vec<String>,
+}
+
+// a command line is split on whitespace, a list is used as it is
+fn command_line<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum CommandLine {
+        Line(String),
+        Args(Vec<String>),
+    }
+
+    use serde::Deserialize as _;
+    Ok(Some(match CommandLine::deserialize(deserializer)? {
+        CommandLine::Line(line) => line.split_whitespace().map(String::from).collect(),
+        CommandLine::Args(args) => args,
+    }))
+}
+
+impl Matrix {
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty() && self.toolchains.is_empty()
+    }
+}
+
 #[derive(Default, serde::Serialize, serde::Deserialize)]
 pub struct Options {
     pub nightly: bool,
+    // a toolchain name like `beta` or `1.74.0`, `nightly` takes precedence over this
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    // used when there's no rustup, $CARGO takes precedence over this
+    #[serde(default)]
+    pub cargo: Option<PathBuf>,
+    // a command used in place of cargo, e.g. `"cargo zigbuild"`, or `["/path with spaces/cross"]`
+    #[serde(default, deserialize_with = "command_line")]
+    pub wrapper: Option<Vec<String>>,
+    // build in `target/ccs`, so rust-analyzer's cache isn't invalidated
+    #[serde(default)]
+    pub separate_target_dir: bool,
+    // in seconds, cargo is killed if it takes longer than this
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // arguments for cargo, before the `--`. e.g. `["--locked", "--profile", "ci"]`
+    #[serde(default)]
+    pub cargo_args: Vec<String>,
+    // raw arguments for the compiler, after the lints
+    #[serde(default)]
+    pub lint_args: Vec<String>,
     pub explain: bool,
+    #[serde(default)]
+    pub explain_lints: bool,
     pub include_notes: bool,
     pub delimiter: String,
     pub new_line: bool,
+    #[serde(default)]
+    pub ascii: bool,
+    #[serde(default)]
+    pub format: Format,
+    #[serde(default)]
+    pub sort: Vec<SortBy>,
+    #[serde(default)]
+    pub color: ColorChoice,
+    #[serde(default)]
+    pub hyperlinks: LinkChoice,
+    #[serde(default)]
+    pub editor_url: Option<String>,
+    // the name of a theme in the `themes` directory, this replaces the `theme` table
+    #[serde(default)]
+    pub theme: Option<String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -27,12 +103,25 @@ pub struct Config {
     pub tool: Tool,
     pub lints: Lints,
     pub options: Options,
-    pub theme: Theme,
+    // this is kept as strings so `Theme::load` can report any problems
+    #[serde(default)]
+    pub theme: BTreeMap<String, String>,
     pub continuation: Option<Cow<'static, str>>,
+    #[serde(default)]
+    pub templates: Templates,
+    #[serde(default)]
+    pub filters: Vec<Filter>,
+    #[serde(default)]
+    pub only: Vec<Only>,
+    #[serde(default)]
+    pub remap: Vec<Remap>,
+    #[serde(default)]
+    pub matrix: Matrix,
 }
 
 impl Config {
     pub const CONTINUATION: Cow<'static, str> = Cow::Borrowed("⮡");
+    pub const ASCII_CONTINUATION: Cow<'static, str> = Cow::Borrowed("->");
 }
 
 impl Default for Config {
@@ -41,8 +130,13 @@ impl Default for Config {
             tool: Default::default(),
             lints: Default::default(),
             options: Default::default(),
-            theme: Default::default(),
+            theme: Theme::DEFAULT.to_map(),
             continuation: Some(Self::CONTINUATION),
+            templates: Templates::default(),
+            filters: Vec::new(),
+            only: Vec::new(),
+            remap: Vec::new(),
+            matrix: Matrix::default(),
         }
     }
 }
@@ -52,6 +146,7 @@ impl Config {
     const ORGANIZATION: &'static str = "museun";
     const APPLICATION: &'static str = env!("CARGO_PKG_NAME");
     const CONFIG_FILE_NAME: &'static str = "ccs.toml";
+    const THEMES_DIR_NAME: &'static str = "themes";
 
     // TODO: will this ever actually be used?
     pub fn save(&self, path: &Path) -> anyhow::Result<()> {
@@ -77,4 +172,38 @@ impl Config {
         )
         .map(|s| s.config_dir().join(Self::CONFIG_FILE_NAME))
     }
+
+    /// The `themes` directory next to the configuration file
+    pub fn get_themes_dir() -> Option<PathBuf> {
+        Self::get_config_path()?
+            .parent()
+            .map(|dir| dir.join(Self::THEMES_DIR_NAME))
+    }
+
+    /// Finds a theme by name (`themes/<name>.toml` in the config directory), or by its path
+    pub fn get_theme_path(name: &str) -> Option<PathBuf> {
+        let path = Path::new(name);
+        if path.extension().is_some_and(|ext| ext == "toml") && path.is_file() {
+            return Some(path.to_path_buf());
+        }
+
+        Self::get_themes_dir().map(|dir| dir.join(format!("{name}.toml")))
+    }
+
+    /// The names of the themes in the `themes` directory
+    pub fn theme_names() -> Vec<String> {
+        let Some(entries) = Self::get_themes_dir().and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut names = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
 }
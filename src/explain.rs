@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::Context as _;
+use owo_colors::OwoColorize as _;
+
+use crate::{lints::is_error_code, Theme};
+
+/// Gets the explanation for a lint or an error code, using the toolchain that `cargo` belongs to
+///
+/// - `clippy::*` lints use `cargo clippy --explain`
+/// - error codes (`E0308`) use `rustc --explain`
+/// - other rustc lints only have a short description, from `rustc -W help`
+pub fn explain(cargo: &Path, code: &str) -> anyhow::Result<String> {
+    let code = code.trim();
+    if code.starts_with("clippy::") {
+        return run(std::process::Command::new(cargo).args(["clippy", "--explain", code]));
+    }
+
+    let rustc = find_rustc(cargo);
+    if is_error_code(code) {
+        return run(std::process::Command::new(rustc).args(["--explain", code]));
+    }
+
+    // lints are listed with dashes: `unused-imports  warn  imports that are never used`
+    let name = code.replace('_', "-");
+    rustc_lints(&rustc)?
+        .get(&name)
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .with_context(|| format!("no explanation found for `{code}`"))
+}
+
+// NOTE `rustc -W help` lists every lint, so it's only run once. a failure is kept as well
+static RUSTC_LINTS: OnceLock<Result<HashMap<String, String>, String>> = OnceLock::new();
+
+fn rustc_lints(rustc: &Path) -> anyhow::Result<&'static HashMap<String, String>> {
+    RUSTC_LINTS
+        .get_or_init(|| {
+            let text = run(std::process::Command::new(rustc).args(["-W", "help"]))
+                .map_err(|err| format!("{err:#}"))?;
+
+            let mut lints = HashMap::new();
+            for line in text.lines() {
+                let mut iter = line.split_whitespace();
+                let (Some(name), Some(_default)) = (iter.next(), iter.next()) else {
+                    continue;
+                };
+                // the lints come before the groups, and those shouldn't replace them
+                lints
+                    .entry(name.to_string())
+                    .or_insert_with(|| iter.collect::<Vec<_>>().join(" "));
+            }
+            Ok(lints)
+        })
+        .as_ref()
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// The first paragraph of an explanation, without any headings
+pub fn explain_summary(text: &str) -> Option<String> {
+    let summary = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'))
+        .take_while(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("```"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!summary.is_empty()).then_some(summary)
+}
+
+/// Renders the markdown-ish explanation with the theme
+pub fn render_explanation(
+    text: &str,
+    theme: &Theme,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            writeln!(out, "    {line}", line = theme.code.paint(line))?;
+            continue;
+        }
+
+        if line.starts_with('#') {
+            let heading = line.trim_start_matches('#').trim();
+            writeln!(
+                out,
+                "{heading}",
+                heading = theme.lint_name.paint(heading).bold()
+            )?;
+            continue;
+        }
+
+        render_inline(line, theme, out)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Renders a single line, highlighting `inline code`
+fn render_inline(line: &str, theme: &Theme, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    for (i, part) in line.split('`').enumerate() {
+        if i % 2 == 1 {
+            write!(out, "{part}", part = theme.highlight.paint(part))?;
+        } else {
+            write!(out, "{part}", part = theme.message.paint(part))?;
+        }
+    }
+    Ok(())
+}
+
+// rustc lives next to cargo in a toolchain
+fn find_rustc(cargo: &Path) -> PathBuf {
+    let rustc = format!("rustc{suffix}", suffix = std::env::consts::EXE_SUFFIX);
+    cargo
+        .parent()
+        .map(|dir| dir.join(&rustc))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(rustc))
+}
+
+fn run(cmd: &mut std::process::Command) -> anyhow::Result<String> {
+    let output = cmd
+        .output()
+        .with_context(|| format!("cannot run {:?}", cmd.get_program()))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || stdout.trim().is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim().trim_start_matches("error: ");
+        anyhow::bail!("{stderr}")
+    }
+
+    Ok(stdout.into_owned())
+}
@@ -0,0 +1,175 @@
+use std::{
+    io::IsTerminal as _,
+    path::{Path, PathBuf},
+};
+
+use clap::{builder::PossibleValue, ValueEnum};
+
+/// When to emit OSC 8 hyperlinks
+#[derive(Copy, Clone, Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum LinkChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ValueEnum for LinkChoice {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Auto, Self::Always, Self::Never]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Auto => PossibleValue::new("auto"),
+            Self::Always => PossibleValue::new("always"),
+            Self::Never => PossibleValue::new("never"),
+        })
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub enum Hyperlinks {
+    #[default]
+    None,
+    /// Link locations with `file://` urls, relative paths are resolved from `root`
+    File { root: PathBuf },
+    /// Link locations with an editor url template, e.g. `vscode://file/{path}:{line}:{col}`
+    Editor { root: PathBuf, template: String },
+}
+
+impl Hyperlinks {
+    pub fn new(choice: LinkChoice, root: PathBuf, template: Option<String>) -> Self {
+        let enabled = match choice {
+            LinkChoice::Auto => std::io::stdout().is_terminal() && is_supported(),
+            LinkChoice::Always => true,
+            LinkChoice::Never => false,
+        };
+
+        match template.filter(|s| !s.is_empty()) {
+            _ if !enabled => Self::None,
+            Some(template) => Self::Editor { root, template },
+            None => Self::File { root },
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        !matches!(self, Self::None)
+    }
+
+    pub fn location_url(&self, file: &str, line: usize, col: usize) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::File { root } => Some(file_url(&resolve(root, file))),
+            Self::Editor { root, template } => {
+                let path = resolve(root, file);
+                Some(
+                    template
+                        .replace("{path}", &path.to_string_lossy())
+                        .replace("{line}", &line.to_string())
+                        .replace("{col}", &col.to_string()),
+                )
+            }
+        }
+    }
+
+    pub fn lint_url(&self, code: &str) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        lint_url(code)
+    }
+}
+
+/// The documentation for a clippy lint or an error code
+pub(crate) fn lint_url(code: &str) -> Option<String> {
+    if let Some(lint) = code.strip_prefix("clippy::") {
+        return Some(format!(
+            "https://rust-lang.github.io/rust-clippy/master/index.html#{lint}"
+        ));
+    }
+
+    crate::lints::is_error_code(code)
+        .then(|| format!("https://doc.rust-lang.org/error_codes/{code}.html"))
+}
+
+/// Writes `text` wrapped in an OSC 8 hyperlink, if there's a url
+pub fn write_link(
+    out: &mut dyn std::io::Write,
+    url: Option<&str>,
+    text: impl std::fmt::Display,
+) -> std::io::Result<()> {
+    match url {
+        Some(url) => write!(out, "\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\"),
+        None => write!(out, "{text}"),
+    }
+}
+
+// NOTE there isn't a way to query for this, so this is the same set of heuristics most tools use
+fn is_supported() -> bool {
+    let var = |key| std::env::var(key).ok().filter(|s| !s.is_empty());
+
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    if var("CI").is_some() || var("TERM").is_some_and(|term| term == "dumb") {
+        return false;
+    }
+
+    if var("WT_SESSION").is_some() || var("KONSOLE_VERSION").is_some() || var("DOMTERM").is_some() {
+        return true;
+    }
+
+    if var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) >= Some(5000) {
+        return true;
+    }
+
+    if let Some(program) = var("TERM_PROGRAM") {
+        if matches!(
+            &*program,
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "rio"
+        ) {
+            return true;
+        }
+    }
+
+    var("TERM").is_some_and(|term| {
+        matches!(
+            &*term,
+            "xterm-kitty" | "alacritty" | "foot" | "xterm-ghostty" | "wezterm"
+        )
+    })
+}
+
+// spans are relative to the workspace root, which may be above the manifest that was used
+pub(crate) fn resolve(root: &Path, file: &str) -> PathBuf {
+    let file = Path::new(file);
+    if file.is_absolute() {
+        return file.to_path_buf();
+    }
+
+    root.ancestors()
+        .map(|dir| dir.join(file))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| root.join(file))
+}
+
+pub(crate) fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+
+    let mut out = String::from("file://");
+    if !path.starts_with('/') {
+        out.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                out.push(byte as char)
+            }
+            byte => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
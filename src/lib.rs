@@ -1,34 +1,45 @@
 mod args;
-pub use args::{Args, Tool};
+pub use args::{Args, SubCommand, Tool};
 
 mod command;
-pub use command::{Command, Extra, Features, Options, Target, Toolchain};
+pub use command::{cargo_profile, Command, Extra, Features, Options, Target, Toolchain};
 
 mod parse;
-pub use parse::{gather_reasons, Reason};
+pub use parse::{gather_reasons, iter_raw_reasons, iter_reasons, Level, Message, Reason};
 
-pub fn find_cargo(toolchain: Toolchain) -> Option<String> {
-    let mut cmd = std::process::Command::new("rustup");
-    if let Some(toolchain) = toolchain.as_str() {
-        cmd.arg(toolchain);
-    }
+mod cargo;
+pub use cargo::{Cargo, CargoSource};
 
-    let mut output = cmd
-        .args(["which", "cargo"])
-        .output()
-        .ok()
-        .map(|c| String::from_utf8(c.stdout))?
-        .ok()?;
+mod metadata;
+pub use metadata::{Metadata, Package};
 
-    output.drain(output.trim_end().len()..);
-    Some(output)
-}
+mod powerset;
+pub use powerset::{FeatureSet, Powerset};
+
+mod process;
+pub use process::{interrupted, Finished, Process};
+
+mod explain;
+pub use explain::{explain, explain_summary, render_explanation};
+
+mod lsp;
+pub use lsp::run_lsp;
+
+mod hyperlinks;
+pub use hyperlinks::{Hyperlinks, LinkChoice};
 
 mod theme;
-pub use theme::Theme;
+pub use theme::{Color, ColorChoice, ColorDepth, Report, Style, Theme};
+
+mod template;
+pub use template::{LevelTemplates, Placeholder, Template, Templates, Value};
 
 mod options;
-pub use options::{Filter, IncludeNotes, RenderOptions, RenderStyle};
+pub use options::{
+    Emit, Expression, Filter, Format, IncludeNotes, Only, Remap, RenderOptions, RenderStyle, SortBy,
+};
+
+mod lints;
 
 mod config;
-pub use config::Config;
+pub use config::{Config, Matrix};
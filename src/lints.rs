@@ -0,0 +1,39 @@
+mod groups;
+use groups::GROUPS;
+
+// `clippy::all` isn't listed with its members, its just the default groups
+const CLIPPY_ALL: &[&str] = &[
+    "clippy::complexity",
+    "clippy::correctness",
+    "clippy::perf",
+    "clippy::style",
+    "clippy::suspicious",
+];
+
+fn normalize(name: &str) -> String {
+    name.trim().replace('-', "_").to_ascii_lowercase()
+}
+
+/// Is this the name of a known lint group (e.g. `clippy::perf` or `unused`)?
+pub fn is_group(name: &str) -> bool {
+    let name = normalize(name);
+    name == "clippy::all" || GROUPS.iter().any(|(group, _)| *group == name)
+}
+
+/// Is `lint` a member of `group`?
+pub fn in_group(group: &str, lint: &str) -> bool {
+    let (group, lint) = (normalize(group), normalize(lint));
+    if group == "clippy::all" {
+        return CLIPPY_ALL.iter().any(|group| in_group(group, &lint));
+    }
+
+    GROUPS
+        .iter()
+        .filter(|(name, _)| *name == group)
+        .any(|(_, lints)| lints.contains(&&*lint))
+}
+
+/// Is this a rustc error code, like `E0308`?
+pub fn is_error_code(code: &str) -> bool {
+    code.len() == 5 && code.starts_with('E') && code[1..].chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,1007 @@
+// NOTE this table is generated from the output of `clippy-driver -W help` (clippy 0.1.95)
+pub const GROUPS: &[(&str, &[&str])] = &[
+    ("deprecated_safe", &["deprecated_safe_2024"]),
+    (
+        "future_incompatible",
+        &[
+            "aarch64_softfloat_neon",
+            "ambiguous_associated_items",
+            "ambiguous_derive_helpers",
+            "ambiguous_glob_imported_traits",
+            "ambiguous_glob_imports",
+            "ambiguous_import_visibilities",
+            "ambiguous_panic_imports",
+            "coherence_leak_check",
+            "conflicting_repr_hints",
+            "const_evaluatable_unchecked",
+            "elided_lifetimes_in_associated_constant",
+            "forbidden_lint_groups",
+            "ill_formed_attribute_input",
+            "internal_eq_trait_method_impls",
+            "invalid_macro_export_arguments",
+            "invalid_type_param_default",
+            "late_bound_lifetime_arguments",
+            "legacy_derive_helpers",
+            "macro_expanded_macro_exports_accessed_by_absolute_paths",
+            "out_of_scope_macro_calls",
+            "patterns_in_fns_without_body",
+            "proc_macro_derive_resolution_fallback",
+            "pub_use_of_private_extern_crate",
+            "repr_c_enums_larger_than_int",
+            "repr_transparent_non_zst_fields",
+            "self_constructor_from_outer_item",
+            "semicolon_in_expressions_from_macros",
+            "soft_unstable",
+            "uncovered_param_in_projection",
+            "uninhabited_static",
+            "unstable_name_collisions",
+            "unstable_syntax_pre_expansion",
+            "unsupported_calling_conventions",
+            "varargs_without_pattern",
+        ],
+    ),
+    (
+        "keyword_idents",
+        &["keyword_idents_2018", "keyword_idents_2024"],
+    ),
+    (
+        "let_underscore",
+        &["let_underscore_drop", "let_underscore_lock"],
+    ),
+    (
+        "nonstandard_style",
+        &[
+            "non_camel_case_types",
+            "non_snake_case",
+            "non_upper_case_globals",
+        ],
+    ),
+    (
+        "refining_impl_trait",
+        &[
+            "refining_impl_trait_internal",
+            "refining_impl_trait_reachable",
+        ],
+    ),
+    (
+        "rust_2018_compatibility",
+        &[
+            "absolute_paths_not_starting_with_crate",
+            "anonymous_parameters",
+            "keyword_idents_2018",
+            "tyvar_behind_raw_pointer",
+        ],
+    ),
+    (
+        "rust_2018_idioms",
+        &[
+            "bare_trait_objects",
+            "elided_lifetimes_in_paths",
+            "ellipsis_inclusive_range_patterns",
+            "explicit_outlives_requirements",
+            "unused_extern_crates",
+        ],
+    ),
+    (
+        "rust_2021_compatibility",
+        &[
+            "array_into_iter",
+            "bare_trait_objects",
+            "ellipsis_inclusive_range_patterns",
+            "non_fmt_panics",
+            "rust_2021_incompatible_closure_captures",
+            "rust_2021_incompatible_or_patterns",
+            "rust_2021_prefixes_incompatible_syntax",
+            "rust_2021_prelude_collisions",
+        ],
+    ),
+    (
+        "rust_2024_compatibility",
+        &[
+            "boxed_slice_into_iter",
+            "dependency_on_unit_never_type_fallback",
+            "deprecated_safe_2024",
+            "edition_2024_expr_fragment_specifier",
+            "if_let_rescope",
+            "impl_trait_overcaptures",
+            "keyword_idents_2024",
+            "missing_unsafe_on_extern",
+            "never_type_fallback_flowing_into_unsafe",
+            "rust_2024_guarded_string_incompatible_syntax",
+            "rust_2024_incompatible_pat",
+            "rust_2024_prelude_collisions",
+            "static_mut_refs",
+            "tail_expr_drop_order",
+            "unsafe_attr_outside_unsafe",
+            "unsafe_op_in_unsafe_fn",
+        ],
+    ),
+    (
+        "unknown_or_malformed_diagnostic_attributes",
+        &[
+            "malformed_diagnostic_attributes",
+            "malformed_diagnostic_format_literals",
+            "misplaced_diagnostic_attributes",
+            "unknown_diagnostic_attributes",
+        ],
+    ),
+    (
+        "unused",
+        &[
+            "dead_code",
+            "map_unit_fn",
+            "path_statements",
+            "redundant_semicolons",
+            "unreachable_code",
+            "unreachable_patterns",
+            "unused_allocation",
+            "unused_assignments",
+            "unused_attributes",
+            "unused_braces",
+            "unused_doc_comments",
+            "unused_extern_crates",
+            "unused_features",
+            "unused_imports",
+            "unused_labels",
+            "unused_macro_rules",
+            "unused_macros",
+            "unused_must_use",
+            "unused_mut",
+            "unused_parens",
+            "unused_unsafe",
+            "unused_variables",
+            "unused_visibilities",
+        ],
+    ),
+    (
+        "clippy::cargo",
+        &[
+            "clippy::cargo_common_metadata",
+            "clippy::multiple_crate_versions",
+            "clippy::negative_feature_names",
+            "clippy::redundant_feature_names",
+            "clippy::wildcard_dependencies",
+        ],
+    ),
+    (
+        "clippy::complexity",
+        &[
+            "clippy::bind_instead_of_map",
+            "clippy::bool_comparison",
+            "clippy::borrow_deref_ref",
+            "clippy::borrowed_box",
+            "clippy::bytes_count_to_len",
+            "clippy::char_lit_as_u8",
+            "clippy::clone_on_copy",
+            "clippy::default_constructed_unit_structs",
+            "clippy::deprecated_cfg_attr",
+            "clippy::deref_addrof",
+            "clippy::derivable_impls",
+            "clippy::diverging_sub_expression",
+            "clippy::double_comparisons",
+            "clippy::double_parens",
+            "clippy::duration_subsec",
+            "clippy::excessive_nesting",
+            "clippy::explicit_auto_deref",
+            "clippy::explicit_counter_loop",
+            "clippy::explicit_write",
+            "clippy::extra_unused_lifetimes",
+            "clippy::extra_unused_type_parameters",
+            "clippy::filter_map_identity",
+            "clippy::filter_next",
+            "clippy::flat_map_identity",
+            "clippy::get_last_with_len",
+            "clippy::identity_op",
+            "clippy::implied_bounds_in_impls",
+            "clippy::inspect_for_each",
+            "clippy::int_plus_one",
+            "clippy::iter_count",
+            "clippy::iter_kv_map",
+            "clippy::let_with_type_underscore",
+            "clippy::manual_abs_diff",
+            "clippy::manual_c_str_literals",
+            "clippy::manual_checked_ops",
+            "clippy::manual_clamp",
+            "clippy::manual_div_ceil",
+            "clippy::manual_filter",
+            "clippy::manual_filter_map",
+            "clippy::manual_find",
+            "clippy::manual_find_map",
+            "clippy::manual_flatten",
+            "clippy::manual_hash_one",
+            "clippy::manual_inspect",
+            "clippy::manual_is_multiple_of",
+            "clippy::manual_main_separator_str",
+            "clippy::manual_ok_err",
+            "clippy::manual_option_as_slice",
+            "clippy::manual_range_patterns",
+            "clippy::manual_rem_euclid",
+            "clippy::manual_slice_size_calculation",
+            "clippy::manual_split_once",
+            "clippy::manual_strip",
+            "clippy::manual_swap",
+            "clippy::manual_take",
+            "clippy::manual_unwrap_or",
+            "clippy::map_all_any_identity",
+            "clippy::map_flatten",
+            "clippy::map_identity",
+            "clippy::match_as_ref",
+            "clippy::match_single_binding",
+            "clippy::needless_arbitrary_self_type",
+            "clippy::needless_as_bytes",
+            "clippy::needless_bool",
+            "clippy::needless_bool_assign",
+            "clippy::needless_borrowed_reference",
+            "clippy::needless_ifs",
+            "clippy::needless_lifetimes",
+            "clippy::needless_match",
+            "clippy::needless_option_as_deref",
+            "clippy::needless_option_take",
+            "clippy::needless_question_mark",
+            "clippy::needless_splitn",
+            "clippy::needless_update",
+            "clippy::neg_cmp_op_on_partial_ord",
+            "clippy::no_effect",
+            "clippy::nonminimal_bool",
+            "clippy::only_used_in_recursion",
+            "clippy::option_as_ref_deref",
+            "clippy::option_filter_map",
+            "clippy::option_map_unit_fn",
+            "clippy::or_then_unwrap",
+            "clippy::partialeq_ne_impl",
+            "clippy::precedence",
+            "clippy::ptr_offset_with_cast",
+            "clippy::range_zip_with_len",
+            "clippy::redundant_as_str",
+            "clippy::redundant_async_block",
+            "clippy::redundant_at_rest_pattern",
+            "clippy::redundant_closure_call",
+            "clippy::redundant_guards",
+            "clippy::redundant_slicing",
+            "clippy::repeat_once",
+            "clippy::reserve_after_initialization",
+            "clippy::result_filter_map",
+            "clippy::result_map_unit_fn",
+            "clippy::seek_from_current",
+            "clippy::seek_to_start_instead_of_rewind",
+            "clippy::short_circuit_statement",
+            "clippy::single_element_loop",
+            "clippy::skip_while_next",
+            "clippy::string_from_utf8_as_bytes",
+            "clippy::strlen_on_c_strings",
+            "clippy::swap_with_temporary",
+            "clippy::temporary_assignment",
+            "clippy::too_many_arguments",
+            "clippy::transmute_bytes_to_str",
+            "clippy::transmute_int_to_bool",
+            "clippy::transmute_int_to_non_zero",
+            "clippy::transmute_ptr_to_ref",
+            "clippy::transmutes_expressible_as_ptr_casts",
+            "clippy::type_complexity",
+            "clippy::unit_arg",
+            "clippy::unnecessary_cast",
+            "clippy::unnecessary_filter_map",
+            "clippy::unnecessary_find_map",
+            "clippy::unnecessary_first_then_check",
+            "clippy::unnecessary_literal_unwrap",
+            "clippy::unnecessary_map_on_constructor",
+            "clippy::unnecessary_min_or_max",
+            "clippy::unnecessary_operation",
+            "clippy::unnecessary_sort_by",
+            "clippy::unnecessary_unwrap",
+            "clippy::unneeded_wildcard_pattern",
+            "clippy::unused_format_specs",
+            "clippy::useless_asref",
+            "clippy::useless_concat",
+            "clippy::useless_conversion",
+            "clippy::useless_format",
+            "clippy::useless_nonzero_new_unchecked",
+            "clippy::useless_transmute",
+            "clippy::vec_box",
+            "clippy::while_let_loop",
+            "clippy::wildcard_in_or_patterns",
+            "clippy::zero_divided_by_zero",
+            "clippy::zero_prefixed_literal",
+        ],
+    ),
+    (
+        "clippy::correctness",
+        &[
+            "clippy::absurd_extreme_comparisons",
+            "clippy::almost_swapped",
+            "clippy::approx_constant",
+            "clippy::async_yields_async",
+            "clippy::bad_bit_mask",
+            "clippy::cast_slice_different_sizes",
+            "clippy::char_indices_as_byte_indices",
+            "clippy::deprecated_semver",
+            "clippy::derive_ord_xor_partial_ord",
+            "clippy::derived_hash_with_manual_eq",
+            "clippy::eager_transmute",
+            "clippy::enum_clike_unportable_variant",
+            "clippy::eq_op",
+            "clippy::erasing_op",
+            "clippy::if_let_mutex",
+            "clippy::ifs_same_cond",
+            "clippy::impl_hash_borrow_with_str_and_bytes",
+            "clippy::impossible_comparisons",
+            "clippy::ineffective_bit_mask",
+            "clippy::infinite_iter",
+            "clippy::inherent_to_string_shadow_display",
+            "clippy::inline_fn_without_body",
+            "clippy::invalid_regex",
+            "clippy::inverted_saturating_sub",
+            "clippy::invisible_characters",
+            "clippy::iter_next_loop",
+            "clippy::iter_skip_zero",
+            "clippy::iterator_step_by_zero",
+            "clippy::let_underscore_lock",
+            "clippy::lint_groups_priority",
+            "clippy::match_str_case_mismatch",
+            "clippy::mem_replace_with_uninit",
+            "clippy::min_max",
+            "clippy::mistyped_literal_suffixes",
+            "clippy::modulo_one",
+            "clippy::mut_from_ref",
+            "clippy::never_loop",
+            "clippy::non_octal_unix_permissions",
+            "clippy::nonsensical_open_options",
+            "clippy::not_unsafe_ptr_arg_deref",
+            "clippy::option_env_unwrap",
+            "clippy::out_of_bounds_indexing",
+            "clippy::overly_complex_bool_expr",
+            "clippy::panicking_overflow_checks",
+            "clippy::panicking_unwrap",
+            "clippy::possible_missing_comma",
+            "clippy::read_line_without_trim",
+            "clippy::recursive_format_impl",
+            "clippy::redundant_comparisons",
+            "clippy::reversed_empty_ranges",
+            "clippy::self_assignment",
+            "clippy::serde_api_misuse",
+            "clippy::size_of_in_element_count",
+            "clippy::suspicious_splitn",
+            "clippy::transmute_null_to_fn",
+            "clippy::transmuting_null",
+            "clippy::uninit_assumed_init",
+            "clippy::uninit_vec",
+            "clippy::unit_cmp",
+            "clippy::unit_hash",
+            "clippy::unit_return_expecting_ord",
+            "clippy::unsound_collection_transmute",
+            "clippy::unused_io_amount",
+            "clippy::useless_attribute",
+            "clippy::vec_resize_to_zero",
+            "clippy::while_immutable_condition",
+            "clippy::wrong_transmute",
+            "clippy::zst_offset",
+        ],
+    ),
+    (
+        "clippy::nursery",
+        &[
+            "clippy::as_ptr_cast_mut",
+            "clippy::branches_sharing_code",
+            "clippy::clear_with_drain",
+            "clippy::coerce_container_to_any",
+            "clippy::collection_is_never_read",
+            "clippy::debug_assert_with_mut_call",
+            "clippy::derive_partial_eq_without_eq",
+            "clippy::doc_link_code",
+            "clippy::equatable_if_let",
+            "clippy::fallible_impl_from",
+            "clippy::future_not_send",
+            "clippy::imprecise_flops",
+            "clippy::iter_on_empty_collections",
+            "clippy::iter_on_single_items",
+            "clippy::iter_with_drain",
+            "clippy::large_stack_frames",
+            "clippy::literal_string_with_formatting_args",
+            "clippy::missing_const_for_fn",
+            "clippy::needless_collect",
+            "clippy::needless_pass_by_ref_mut",
+            "clippy::needless_type_cast",
+            "clippy::non_send_fields_in_send_ty",
+            "clippy::nonstandard_macro_braces",
+            "clippy::option_if_let_else",
+            "clippy::or_fun_call",
+            "clippy::path_buf_push_overwrite",
+            "clippy::read_zero_byte_vec",
+            "clippy::redundant_clone",
+            "clippy::redundant_pub_crate",
+            "clippy::search_is_some",
+            "clippy::set_contains_or_insert",
+            "clippy::significant_drop_in_scrutinee",
+            "clippy::significant_drop_tightening",
+            "clippy::single_option_map",
+            "clippy::string_lit_as_bytes",
+            "clippy::suboptimal_flops",
+            "clippy::suspicious_operation_groupings",
+            "clippy::too_long_first_doc_paragraph",
+            "clippy::trailing_empty_array",
+            "clippy::trait_duplication_in_bounds",
+            "clippy::transmute_undefined_repr",
+            "clippy::trivial_regex",
+            "clippy::tuple_array_conversions",
+            "clippy::type_repetition_in_bounds",
+            "clippy::uninhabited_references",
+            "clippy::unnecessary_struct_initialization",
+            "clippy::unused_peekable",
+            "clippy::unused_rounding",
+            "clippy::use_self",
+            "clippy::useless_let_if_seq",
+            "clippy::volatile_composites",
+            "clippy::while_float",
+        ],
+    ),
+    (
+        "clippy::pedantic",
+        &[
+            "clippy::assigning_clones",
+            "clippy::bool_to_int_with_if",
+            "clippy::borrow_as_ptr",
+            "clippy::case_sensitive_file_extension_comparisons",
+            "clippy::cast_lossless",
+            "clippy::cast_possible_truncation",
+            "clippy::cast_possible_wrap",
+            "clippy::cast_precision_loss",
+            "clippy::cast_ptr_alignment",
+            "clippy::cast_sign_loss",
+            "clippy::checked_conversions",
+            "clippy::cloned_instead_of_copied",
+            "clippy::collapsible_else_if",
+            "clippy::comparison_chain",
+            "clippy::copy_iterator",
+            "clippy::decimal_bitwise_operands",
+            "clippy::default_trait_access",
+            "clippy::doc_broken_link",
+            "clippy::doc_comment_double_space_linebreaks",
+            "clippy::doc_link_with_quotes",
+            "clippy::doc_markdown",
+            "clippy::duration_suboptimal_units",
+            "clippy::elidable_lifetime_names",
+            "clippy::empty_enums",
+            "clippy::enum_glob_use",
+            "clippy::expl_impl_clone_on_copy",
+            "clippy::explicit_deref_methods",
+            "clippy::explicit_into_iter_loop",
+            "clippy::explicit_iter_loop",
+            "clippy::filter_map_next",
+            "clippy::flat_map_option",
+            "clippy::float_cmp",
+            "clippy::fn_params_excessive_bools",
+            "clippy::format_collect",
+            "clippy::format_push_string",
+            "clippy::from_iter_instead_of_collect",
+            "clippy::if_not_else",
+            "clippy::ignore_without_reason",
+            "clippy::ignored_unit_patterns",
+            "clippy::implicit_clone",
+            "clippy::implicit_hasher",
+            "clippy::inconsistent_struct_constructor",
+            "clippy::index_refutable_slice",
+            "clippy::inefficient_to_string",
+            "clippy::inline_always",
+            "clippy::into_iter_without_iter",
+            "clippy::invalid_upcast_comparisons",
+            "clippy::ip_constant",
+            "clippy::items_after_statements",
+            "clippy::iter_filter_is_ok",
+            "clippy::iter_filter_is_some",
+            "clippy::iter_not_returning_iterator",
+            "clippy::iter_without_into_iter",
+            "clippy::large_digit_groups",
+            "clippy::large_futures",
+            "clippy::large_stack_arrays",
+            "clippy::large_types_passed_by_value",
+            "clippy::linkedlist",
+            "clippy::macro_use_imports",
+            "clippy::manual_assert",
+            "clippy::manual_ilog2",
+            "clippy::manual_instant_elapsed",
+            "clippy::manual_is_power_of_two",
+            "clippy::manual_is_variant_and",
+            "clippy::manual_let_else",
+            "clippy::manual_midpoint",
+            "clippy::manual_string_new",
+            "clippy::many_single_char_names",
+            "clippy::map_unwrap_or",
+            "clippy::match_bool",
+            "clippy::match_same_arms",
+            "clippy::match_wild_err_arm",
+            "clippy::match_wildcard_for_single_variants",
+            "clippy::maybe_infinite_iter",
+            "clippy::mismatching_type_param_order",
+            "clippy::missing_errors_doc",
+            "clippy::missing_fields_in_debug",
+            "clippy::missing_panics_doc",
+            "clippy::must_use_candidate",
+            "clippy::mut_mut",
+            "clippy::naive_bytecount",
+            "clippy::needless_bitwise_bool",
+            "clippy::needless_continue",
+            "clippy::needless_for_each",
+            "clippy::needless_pass_by_value",
+            "clippy::needless_raw_string_hashes",
+            "clippy::no_effect_underscore_binding",
+            "clippy::no_mangle_with_rust_abi",
+            "clippy::non_std_lazy_statics",
+            "clippy::option_as_ref_cloned",
+            "clippy::option_option",
+            "clippy::ptr_as_ptr",
+            "clippy::ptr_cast_constness",
+            "clippy::ptr_offset_by_literal",
+            "clippy::pub_underscore_fields",
+            "clippy::range_minus_one",
+            "clippy::range_plus_one",
+            "clippy::redundant_closure_for_method_calls",
+            "clippy::redundant_else",
+            "clippy::ref_as_ptr",
+            "clippy::ref_binding_to_reference",
+            "clippy::ref_option",
+            "clippy::ref_option_ref",
+            "clippy::return_self_not_must_use",
+            "clippy::same_functions_in_if_condition",
+            "clippy::same_length_and_capacity",
+            "clippy::self_only_used_in_recursion",
+            "clippy::semicolon_if_nothing_returned",
+            "clippy::should_panic_without_expect",
+            "clippy::similar_names",
+            "clippy::single_char_pattern",
+            "clippy::single_match_else",
+            "clippy::stable_sort_primitive",
+            "clippy::str_split_at_newline",
+            "clippy::string_add_assign",
+            "clippy::struct_excessive_bools",
+            "clippy::struct_field_names",
+            "clippy::too_many_lines",
+            "clippy::transmute_ptr_to_ptr",
+            "clippy::trivially_copy_pass_by_ref",
+            "clippy::unchecked_time_subtraction",
+            "clippy::unicode_not_nfc",
+            "clippy::uninlined_format_args",
+            "clippy::unnecessary_box_returns",
+            "clippy::unnecessary_debug_formatting",
+            "clippy::unnecessary_join",
+            "clippy::unnecessary_literal_bound",
+            "clippy::unnecessary_semicolon",
+            "clippy::unnecessary_trailing_comma",
+            "clippy::unnecessary_wraps",
+            "clippy::unnested_or_patterns",
+            "clippy::unreadable_literal",
+            "clippy::unsafe_derive_deserialize",
+            "clippy::unused_async",
+            "clippy::unused_self",
+            "clippy::used_underscore_binding",
+            "clippy::used_underscore_items",
+            "clippy::verbose_bit_mask",
+            "clippy::wildcard_imports",
+            "clippy::zero_sized_map_values",
+        ],
+    ),
+    (
+        "clippy::perf",
+        &[
+            "clippy::box_collection",
+            "clippy::boxed_local",
+            "clippy::cloned_ref_to_slice_refs",
+            "clippy::cmp_owned",
+            "clippy::collapsible_str_replace",
+            "clippy::double_ended_iterator_last",
+            "clippy::drain_collect",
+            "clippy::expect_fun_call",
+            "clippy::extend_with_drain",
+            "clippy::format_in_format_args",
+            "clippy::iter_overeager_cloned",
+            "clippy::large_const_arrays",
+            "clippy::large_enum_variant",
+            "clippy::manual_contains",
+            "clippy::manual_ignore_case_cmp",
+            "clippy::manual_memcpy",
+            "clippy::manual_retain",
+            "clippy::manual_str_repeat",
+            "clippy::manual_try_fold",
+            "clippy::map_entry",
+            "clippy::missing_const_for_thread_local",
+            "clippy::missing_spin_loop",
+            "clippy::readonly_write_lock",
+            "clippy::redundant_allocation",
+            "clippy::redundant_iter_cloned",
+            "clippy::regex_creation_in_loops",
+            "clippy::replace_box",
+            "clippy::result_large_err",
+            "clippy::sliced_string_as_bytes",
+            "clippy::slow_vector_initialization",
+            "clippy::to_string_in_format_args",
+            "clippy::unbuffered_bytes",
+            "clippy::unnecessary_to_owned",
+            "clippy::useless_vec",
+            "clippy::vec_init_then_push",
+            "clippy::waker_clone_wake",
+        ],
+    ),
+    (
+        "clippy::restriction",
+        &[
+            "clippy::absolute_paths",
+            "clippy::alloc_instead_of_core",
+            "clippy::allow_attributes",
+            "clippy::allow_attributes_without_reason",
+            "clippy::arbitrary_source_item_ordering",
+            "clippy::arithmetic_side_effects",
+            "clippy::as_conversions",
+            "clippy::as_pointer_underscore",
+            "clippy::as_underscore",
+            "clippy::assertions_on_result_states",
+            "clippy::big_endian_bytes",
+            "clippy::cfg_not_test",
+            "clippy::clone_on_ref_ptr",
+            "clippy::cognitive_complexity",
+            "clippy::create_dir",
+            "clippy::dbg_macro",
+            "clippy::decimal_literal_representation",
+            "clippy::default_numeric_fallback",
+            "clippy::default_union_representation",
+            "clippy::deref_by_slicing",
+            "clippy::disallowed_script_idents",
+            "clippy::doc_include_without_cfg",
+            "clippy::doc_paragraphs_missing_punctuation",
+            "clippy::else_if_without_else",
+            "clippy::empty_drop",
+            "clippy::empty_enum_variants_with_brackets",
+            "clippy::empty_structs_with_brackets",
+            "clippy::error_impl_error",
+            "clippy::exhaustive_enums",
+            "clippy::exhaustive_structs",
+            "clippy::exit",
+            "clippy::expect_used",
+            "clippy::field_scoped_visibility_modifiers",
+            "clippy::filetype_is_file",
+            "clippy::float_arithmetic",
+            "clippy::float_cmp_const",
+            "clippy::fn_to_numeric_cast_any",
+            "clippy::get_unwrap",
+            "clippy::host_endian_bytes",
+            "clippy::if_then_some_else_none",
+            "clippy::impl_trait_in_params",
+            "clippy::implicit_return",
+            "clippy::indexing_slicing",
+            "clippy::infinite_loop",
+            "clippy::inline_asm_x86_att_syntax",
+            "clippy::inline_asm_x86_intel_syntax",
+            "clippy::integer_division",
+            "clippy::integer_division_remainder_used",
+            "clippy::iter_over_hash_type",
+            "clippy::large_include_file",
+            "clippy::let_underscore_must_use",
+            "clippy::let_underscore_untyped",
+            "clippy::little_endian_bytes",
+            "clippy::lossy_float_literal",
+            "clippy::map_err_ignore",
+            "clippy::map_with_unused_argument_over_ranges",
+            "clippy::mem_forget",
+            "clippy::min_ident_chars",
+            "clippy::missing_assert_message",
+            "clippy::missing_asserts_for_indexing",
+            "clippy::missing_docs_in_private_items",
+            "clippy::missing_inline_in_public_items",
+            "clippy::missing_trait_methods",
+            "clippy::mixed_read_write_in_expression",
+            "clippy::mod_module_files",
+            "clippy::module_name_repetitions",
+            "clippy::modulo_arithmetic",
+            "clippy::multiple_inherent_impl",
+            "clippy::multiple_unsafe_ops_per_block",
+            "clippy::mutex_atomic",
+            "clippy::mutex_integer",
+            "clippy::needless_raw_strings",
+            "clippy::non_ascii_literal",
+            "clippy::non_zero_suggestions",
+            "clippy::panic",
+            "clippy::panic_in_result_fn",
+            "clippy::partial_pub_fields",
+            "clippy::pathbuf_init_then_push",
+            "clippy::pattern_type_mismatch",
+            "clippy::pointer_format",
+            "clippy::precedence_bits",
+            "clippy::print_stderr",
+            "clippy::print_stdout",
+            "clippy::pub_use",
+            "clippy::pub_with_shorthand",
+            "clippy::pub_without_shorthand",
+            "clippy::question_mark_used",
+            "clippy::rc_buffer",
+            "clippy::rc_mutex",
+            "clippy::redundant_test_prefix",
+            "clippy::redundant_type_annotations",
+            "clippy::ref_patterns",
+            "clippy::renamed_function_params",
+            "clippy::rest_pat_in_fully_bound_structs",
+            "clippy::return_and_then",
+            "clippy::same_name_method",
+            "clippy::self_named_module_files",
+            "clippy::semicolon_inside_block",
+            "clippy::semicolon_outside_block",
+            "clippy::separated_literal_suffix",
+            "clippy::shadow_reuse",
+            "clippy::shadow_same",
+            "clippy::shadow_unrelated",
+            "clippy::single_call_fn",
+            "clippy::single_char_lifetime_names",
+            "clippy::std_instead_of_alloc",
+            "clippy::std_instead_of_core",
+            "clippy::str_to_string",
+            "clippy::string_add",
+            "clippy::string_lit_chars_any",
+            "clippy::string_slice",
+            "clippy::suspicious_xor_used_as_pow",
+            "clippy::tests_outside_test_module",
+            "clippy::todo",
+            "clippy::try_err",
+            "clippy::undocumented_unsafe_blocks",
+            "clippy::unimplemented",
+            "clippy::unnecessary_safety_comment",
+            "clippy::unnecessary_safety_doc",
+            "clippy::unnecessary_self_imports",
+            "clippy::unneeded_field_pattern",
+            "clippy::unreachable",
+            "clippy::unseparated_literal_suffix",
+            "clippy::unused_result_ok",
+            "clippy::unused_trait_names",
+            "clippy::unwrap_in_result",
+            "clippy::unwrap_used",
+            "clippy::use_debug",
+            "clippy::verbose_file_reads",
+            "clippy::wildcard_enum_match_arm",
+        ],
+    ),
+    (
+        "clippy::style",
+        &[
+            "clippy::assertions_on_constants",
+            "clippy::assign_op_pattern",
+            "clippy::blocks_in_conditions",
+            "clippy::bool_assert_comparison",
+            "clippy::borrow_interior_mutable_const",
+            "clippy::box_default",
+            "clippy::builtin_type_shadow",
+            "clippy::byte_char_slices",
+            "clippy::bytes_nth",
+            "clippy::chars_last_cmp",
+            "clippy::chars_next_cmp",
+            "clippy::cmp_null",
+            "clippy::collapsible_if",
+            "clippy::collapsible_match",
+            "clippy::comparison_to_empty",
+            "clippy::default_instead_of_iter_empty",
+            "clippy::disallowed_fields",
+            "clippy::disallowed_macros",
+            "clippy::disallowed_methods",
+            "clippy::disallowed_names",
+            "clippy::disallowed_types",
+            "clippy::doc_lazy_continuation",
+            "clippy::doc_overindented_list_items",
+            "clippy::double_must_use",
+            "clippy::duplicate_underscore_argument",
+            "clippy::enum_variant_names",
+            "clippy::err_expect",
+            "clippy::excessive_precision",
+            "clippy::field_reassign_with_default",
+            "clippy::filter_map_bool_then",
+            "clippy::fn_to_numeric_cast",
+            "clippy::fn_to_numeric_cast_with_truncation",
+            "clippy::for_kv_map",
+            "clippy::from_over_into",
+            "clippy::from_str_radix_10",
+            "clippy::get_first",
+            "clippy::if_same_then_else",
+            "clippy::implicit_saturating_add",
+            "clippy::implicit_saturating_sub",
+            "clippy::inconsistent_digit_grouping",
+            "clippy::infallible_destructuring_match",
+            "clippy::inherent_to_string",
+            "clippy::init_numbered_fields",
+            "clippy::into_iter_on_ref",
+            "clippy::io_other_error",
+            "clippy::is_digit_ascii_radix",
+            "clippy::items_after_test_module",
+            "clippy::iter_cloned_collect",
+            "clippy::iter_next_slice",
+            "clippy::iter_nth",
+            "clippy::iter_nth_zero",
+            "clippy::iter_skip_next",
+            "clippy::just_underscores_and_digits",
+            "clippy::legacy_numeric_constants",
+            "clippy::len_without_is_empty",
+            "clippy::len_zero",
+            "clippy::let_and_return",
+            "clippy::let_unit_value",
+            "clippy::main_recursion",
+            "clippy::manual_async_fn",
+            "clippy::manual_bits",
+            "clippy::manual_dangling_ptr",
+            "clippy::manual_is_ascii_check",
+            "clippy::manual_is_finite",
+            "clippy::manual_is_infinite",
+            "clippy::manual_map",
+            "clippy::manual_next_back",
+            "clippy::manual_non_exhaustive",
+            "clippy::manual_ok_or",
+            "clippy::manual_pattern_char_comparison",
+            "clippy::manual_range_contains",
+            "clippy::manual_repeat_n",
+            "clippy::manual_rotate",
+            "clippy::manual_saturating_arithmetic",
+            "clippy::manual_slice_fill",
+            "clippy::manual_while_let_some",
+            "clippy::map_clone",
+            "clippy::map_collect_result_unit",
+            "clippy::match_like_matches_macro",
+            "clippy::match_overlapping_arm",
+            "clippy::match_ref_pats",
+            "clippy::match_result_ok",
+            "clippy::mem_replace_option_with_none",
+            "clippy::mem_replace_option_with_some",
+            "clippy::mem_replace_with_default",
+            "clippy::missing_enforced_import_renames",
+            "clippy::missing_safety_doc",
+            "clippy::mixed_attributes_style",
+            "clippy::mixed_case_hex_literals",
+            "clippy::module_inception",
+            "clippy::multiple_bound_locations",
+            "clippy::must_use_unit",
+            "clippy::mut_mutex_lock",
+            "clippy::needless_borrow",
+            "clippy::needless_borrows_for_generic_args",
+            "clippy::needless_doctest_main",
+            "clippy::needless_else",
+            "clippy::needless_late_init",
+            "clippy::needless_parens_on_range_literals",
+            "clippy::needless_pub_self",
+            "clippy::needless_range_loop",
+            "clippy::needless_return",
+            "clippy::needless_return_with_question_mark",
+            "clippy::neg_multiply",
+            "clippy::new_ret_no_self",
+            "clippy::new_without_default",
+            "clippy::non_minimal_cfg",
+            "clippy::obfuscated_if_else",
+            "clippy::ok_expect",
+            "clippy::op_ref",
+            "clippy::option_map_or_none",
+            "clippy::owned_cow",
+            "clippy::partialeq_to_none",
+            "clippy::print_literal",
+            "clippy::print_with_newline",
+            "clippy::println_empty_string",
+            "clippy::ptr_arg",
+            "clippy::ptr_eq",
+            "clippy::question_mark",
+            "clippy::redundant_closure",
+            "clippy::redundant_field_names",
+            "clippy::redundant_pattern",
+            "clippy::redundant_pattern_matching",
+            "clippy::redundant_static_lifetimes",
+            "clippy::result_map_or_into_option",
+            "clippy::result_unit_err",
+            "clippy::same_item_push",
+            "clippy::self_named_constructors",
+            "clippy::should_implement_trait",
+            "clippy::single_char_add_str",
+            "clippy::single_component_path_imports",
+            "clippy::single_match",
+            "clippy::string_extend_chars",
+            "clippy::tabs_in_doc_comments",
+            "clippy::to_digit_is_some",
+            "clippy::to_string_trait_impl",
+            "clippy::toplevel_ref_arg",
+            "clippy::trim_split_whitespace",
+            "clippy::unnecessary_fallible_conversions",
+            "clippy::unnecessary_fold",
+            "clippy::unnecessary_lazy_evaluations",
+            "clippy::unnecessary_map_or",
+            "clippy::unnecessary_mut_passed",
+            "clippy::unnecessary_owned_empty_strings",
+            "clippy::unneeded_struct_pattern",
+            "clippy::unsafe_removed_from_name",
+            "clippy::unused_enumerate_index",
+            "clippy::unused_unit",
+            "clippy::unusual_byte_groupings",
+            "clippy::unwrap_or_default",
+            "clippy::upper_case_acronyms",
+            "clippy::while_let_on_iterator",
+            "clippy::write_literal",
+            "clippy::write_with_newline",
+            "clippy::writeln_empty_string",
+            "clippy::wrong_self_convention",
+            "clippy::zero_ptr",
+        ],
+    ),
+    (
+        "clippy::suspicious",
+        &[
+            "clippy::almost_complete_range",
+            "clippy::arc_with_non_send_sync",
+            "clippy::await_holding_invalid_type",
+            "clippy::await_holding_lock",
+            "clippy::await_holding_refcell_ref",
+            "clippy::blanket_clippy_restriction_lints",
+            "clippy::cast_abs_to_unsigned",
+            "clippy::cast_enum_constructor",
+            "clippy::cast_enum_truncation",
+            "clippy::cast_nan_to_int",
+            "clippy::cast_slice_from_raw_parts",
+            "clippy::confusing_method_to_numeric_cast",
+            "clippy::const_is_empty",
+            "clippy::crate_in_macro_def",
+            "clippy::crosspointer_transmute",
+            "clippy::declare_interior_mutable_const",
+            "clippy::deprecated_clippy_cfg_attr",
+            "clippy::doc_nested_refdefs",
+            "clippy::doc_suspicious_footnotes",
+            "clippy::drop_non_drop",
+            "clippy::duplicate_mod",
+            "clippy::duplicated_attributes",
+            "clippy::empty_docs",
+            "clippy::empty_line_after_doc_comments",
+            "clippy::empty_line_after_outer_attr",
+            "clippy::empty_loop",
+            "clippy::float_equality_without_abs",
+            "clippy::forget_non_drop",
+            "clippy::four_forward_slashes",
+            "clippy::from_raw_with_void_ptr",
+            "clippy::incompatible_msrv",
+            "clippy::ineffective_open_options",
+            "clippy::infallible_try_from",
+            "clippy::iter_out_of_bounds",
+            "clippy::join_absolute_paths",
+            "clippy::let_underscore_future",
+            "clippy::lines_filter_map_ok",
+            "clippy::macro_metavars_in_unsafe",
+            "clippy::manual_unwrap_or_default",
+            "clippy::misnamed_getters",
+            "clippy::misrefactored_assign_op",
+            "clippy::missing_transmute_annotations",
+            "clippy::multi_assignments",
+            "clippy::mut_range_bound",
+            "clippy::mutable_key_type",
+            "clippy::needless_character_iteration",
+            "clippy::needless_maybe_sized",
+            "clippy::no_effect_replace",
+            "clippy::non_canonical_clone_impl",
+            "clippy::non_canonical_partial_ord_impl",
+            "clippy::octal_escapes",
+            "clippy::path_ends_with_ext",
+            "clippy::permissions_set_readonly_false",
+            "clippy::pointers_in_nomem_asm_block",
+            "clippy::possible_missing_else",
+            "clippy::print_in_format_impl",
+            "clippy::rc_clone_in_vec_init",
+            "clippy::redundant_locals",
+            "clippy::repeat_vec_with_capacity",
+            "clippy::repr_packed_without_abi",
+            "clippy::single_range_in_vec_init",
+            "clippy::size_of_ref",
+            "clippy::suspicious_arithmetic_impl",
+            "clippy::suspicious_assignment_formatting",
+            "clippy::suspicious_command_arg_space",
+            "clippy::suspicious_doc_comments",
+            "clippy::suspicious_else_formatting",
+            "clippy::suspicious_map",
+            "clippy::suspicious_op_assign_impl",
+            "clippy::suspicious_open_options",
+            "clippy::suspicious_to_owned",
+            "clippy::suspicious_unary_op_formatting",
+            "clippy::swap_ptr_to_ref",
+            "clippy::test_attr_in_doctest",
+            "clippy::type_id_on_box",
+            "clippy::unconditional_recursion",
+            "clippy::unnecessary_clippy_cfg",
+            "clippy::unnecessary_get_then_check",
+            "clippy::unnecessary_option_map_or_else",
+            "clippy::unnecessary_result_map_or_else",
+            "clippy::zero_repeat_side_effects",
+            "clippy::zombie_processes",
+        ],
+    ),
+];
@@ -0,0 +1,364 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    hyperlinks::{file_url, lint_url, resolve},
+    iter_reasons,
+    parse::Span,
+    Command, Level, Message, Options, Reason, RenderOptions,
+};
+
+/// Runs a minimal language server on stdin and stdout
+///
+/// The check is run when the client is initialized and whenever a document is saved,
+/// each message that isn't filtered out is published as a diagnostic
+pub fn run_lsp(
+    command: Command<'_>,
+    opts: Options,
+    render_options: RenderOptions,
+) -> anyhow::Result<()> {
+    let mut server = Server {
+        command,
+        opts,
+        render_options,
+        root: std::env::current_dir()?,
+        published: HashSet::new(),
+        out: std::io::stdout(),
+    };
+
+    let mut input = BufReader::new(std::io::stdin().lock());
+    while let Some(input) = read_message(&mut input)? {
+        let request = match input {
+            Input::Message(request) => request,
+            Input::Invalid(err) => {
+                server.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32700, "message": format!("parse error: {err}") },
+                }))?;
+                continue;
+            }
+        };
+
+        // NOTE ctrl-c stops the check that was running, and the server with it
+        if !server.handle(request)? || crate::interrupted() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// a message from the client
+enum Input {
+    Message(Value),
+    // the client gets an error for these, and the server keeps going
+    Invalid(String),
+}
+
+struct Server<'a> {
+    command: Command<'a>,
+    opts: Options,
+    render_options: RenderOptions,
+    root: PathBuf,
+    // uris that have diagnostics, so they can be cleared on the next check
+    published: HashSet<String>,
+    out: std::io::Stdout,
+}
+
+impl Server<'_> {
+    // returns false when the client asks the server to exit
+    fn handle(&mut self, request: Value) -> anyhow::Result<bool> {
+        let id = request.get("id").cloned();
+        let method = request["method"].as_str().unwrap_or_default();
+
+        match (method, id) {
+            ("initialize", Some(id)) => {
+                if let Some(root) = request["params"]["rootUri"].as_str().and_then(file_path) {
+                    self.root = root;
+                }
+
+                // spans are relative to the workspace, so the check has to run from the root
+                let manifest = self.root.join("Cargo.toml");
+                if self.opts.path.is_none() && manifest.is_file() {
+                    self.opts.path = Some(manifest);
+                }
+
+                self.respond(
+                    id,
+                    json!({
+                        "capabilities": {
+                            "textDocumentSync": {
+                                "openClose": true,
+                                "change": 0,
+                                "save": { "includeText": false },
+                            },
+                        },
+                        "serverInfo": {
+                            "name": env!("CARGO_PKG_NAME"),
+                            "version": env!("CARGO_PKG_VERSION"),
+                        },
+                    }),
+                )?;
+            }
+            ("initialized" | "textDocument/didSave", None) => self.check()?,
+            ("shutdown", Some(id)) => self.respond(id, Value::Null)?,
+            ("exit", None) => return Ok(false),
+            (method, Some(id)) => {
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32601,
+                        "message": format!("unsupported method: {method}"),
+                    },
+                }))?;
+            }
+            // other notifications can be ignored
+            (_, None) => {}
+        }
+
+        Ok(true)
+    }
+
+    fn check(&mut self) -> anyhow::Result<()> {
+        let messages = match self.run() {
+            Ok(messages) => messages,
+            Err(err) => {
+                return self.notify(
+                    "window/showMessage",
+                    json!({ "type": 1, "message": format!("ccs: {err:#}") }),
+                );
+            }
+        };
+
+        // the server stops after this, so there's nothing to publish
+        if crate::interrupted() {
+            return Ok(());
+        }
+
+        let mut diagnostics = BTreeMap::<String, Vec<Value>>::new();
+        for message in &messages {
+            let Some(span) = message.primary_span() else {
+                let kind = match message.level {
+                    Level::Error => 1,
+                    Level::Warning => 2,
+                    _ => continue,
+                };
+                self.notify(
+                    "window/showMessage",
+                    json!({ "type": kind, "message": format!("ccs: {}", message.message) }),
+                )?;
+                continue;
+            };
+            let uri = self.uri(span);
+            let diagnostic = self.diagnostic(message, span);
+            diagnostics.entry(uri).or_default().push(diagnostic);
+        }
+
+        // files that no longer have any diagnostics have to be cleared
+        for uri in std::mem::take(&mut self.published) {
+            diagnostics.entry(uri).or_default();
+        }
+
+        for (uri, diagnostics) in diagnostics {
+            if !diagnostics.is_empty() {
+                self.published.insert(uri.clone());
+            }
+            self.notify(
+                "textDocument/publishDiagnostics",
+                json!({ "uri": uri, "diagnostics": diagnostics }),
+            )?;
+        }
+        Ok(())
+    }
+
+    // runs the check, returning the messages that would be shown
+    fn run(&self) -> anyhow::Result<Vec<Message>> {
+        let Some(mut process) = self.command.clone().build_command(self.opts.clone())? else {
+            return Ok(vec![]);
+        };
+        let stdout = process.take_stdout();
+
+        let mut messages = iter_reasons(BufReader::new(stdout))
+            .filter_map(|reason| match reason {
+                Reason::CompilerMessage { mut message } => {
+                    self.render_options.remap_level(&mut message);
+                    let shown = !self.render_options.is_ignored(&message)
+                        && !message.is_hidden(&self.render_options);
+                    shown.then_some(message)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let finished = process.finish();
+        let shown_error = messages
+            .iter()
+            .any(|message| matches!(message.level, Level::Error));
+        let exit_error = finished.exit_error(shown_error);
+
+        // these don't have a span, so they are shown as a message rather than published
+        messages.extend(finished.messages.into_iter().chain(exit_error));
+        Ok(messages)
+    }
+
+    fn diagnostic(&self, message: &Message, span: &Span) -> Value {
+        let severity = match message.level {
+            Level::Error => 1,
+            Level::Warning => 2,
+            Level::Note => 3,
+            Level::Help | Level::FailureNote | Level::Unknown => 4,
+        };
+
+        // children without a span refer to the parent's
+        let related = message
+            .children
+            .iter()
+            .map(|child| {
+                let span = child.primary_span().unwrap_or(span);
+                let level = child.level.as_str();
+                json!({
+                    "location": { "uri": self.uri(span), "range": range(span) },
+                    "message": format!("{level}: {message}", message = child.message),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut diagnostic = json!({
+            "range": range(span),
+            "severity": severity,
+            "source": env!("CARGO_PKG_NAME"),
+            "message": message.message,
+            "relatedInformation": related,
+        });
+
+        if let Some(code) = &message.code {
+            diagnostic["code"] = json!(code.code);
+            if let Some(href) = lint_url(&code.code) {
+                diagnostic["codeDescription"] = json!({ "href": href });
+            }
+        }
+        diagnostic
+    }
+
+    fn uri(&self, span: &Span) -> String {
+        file_url(&resolve(&self.root, &span.file_name))
+    }
+
+    fn respond(&mut self, id: Value, result: Value) -> anyhow::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> anyhow::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn send(&mut self, message: Value) -> anyhow::Result<()> {
+        let body = message.to_string();
+        let mut out = self.out.lock();
+        write!(out, "Content-Length: {len}\r\n\r\n{body}", len = body.len())?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+// lines are 0 indexed and characters are utf-16 code units, rustc uses 1 indexed chars
+fn range(span: &Span) -> Value {
+    let position = |line: usize, col: usize, text: Option<&str>| {
+        let col = col.saturating_sub(1);
+        let character = text.map_or(col, |text| {
+            let byte = str_indices::chars::to_byte_idx(text, col);
+            str_indices::utf16::from_byte_idx(text, byte)
+        });
+        json!({ "line": line.saturating_sub(1), "character": character })
+    };
+
+    let (line_end, column_end) = if span.line_end == 0 {
+        (span.line_start, span.column_start)
+    } else {
+        (span.line_end, span.column_end)
+    };
+
+    json!({
+        "start": position(
+            span.line_start,
+            span.column_start,
+            span.text.first().map(|t| &*t.text),
+        ),
+        "end": position(line_end, column_end, span.text.last().map(|t| &*t.text)),
+    })
+}
+
+// reads a message with its `Content-Length` header, `None` is the end of the input
+fn read_message(input: &mut impl BufRead) -> std::io::Result<Option<Input>> {
+    let mut len = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            if key.eq_ignore_ascii_case("content-length") {
+                len = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| value.trim().to_string()),
+                );
+            }
+        }
+    }
+
+    // NOTE without a length the body can't be skipped, it'll be read as headers
+    let len = match len {
+        Some(Ok(len)) => len,
+        Some(Err(value)) => {
+            let err = format!("invalid Content-Length: {value}");
+            return Ok(Some(Input::Invalid(err)));
+        }
+        None => {
+            let err = String::from("missing the Content-Length header");
+            return Ok(Some(Input::Invalid(err)));
+        }
+    };
+
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+    Ok(Some(match serde_json::from_slice(&body) {
+        Ok(message) => Input::Message(message),
+        Err(err) => Input::Invalid(err.to_string()),
+    }))
+}
+
+fn file_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = [iter.next()?, iter.next()?];
+        bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+    }
+
+    let path = String::from_utf8(bytes).ok()?;
+    // windows uris look like `file:///C:/path`
+    let path = match path.strip_prefix('/') {
+        Some(rest) if rest.get(1..2) == Some(":") => rest,
+        _ => &path,
+    };
+    Some(Path::new(path).to_path_buf())
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+
+use crate::Cargo;
+
+/// The parts of `cargo metadata` that are used
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Metadata {
+    pub target_directory: PathBuf,
+    /// With `--no-deps`, these are only the workspace members
+    pub packages: Vec<Package>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+impl Metadata {
+    /// Runs `cargo metadata` for the manifest, or the one in the current directory
+    pub fn load(cargo: &Cargo, manifest: Option<&Path>) -> anyhow::Result<Self> {
+        let mut cmd = std::process::Command::new(&cargo.path);
+        cmd.args(["metadata", "--format-version=1", "--no-deps"]);
+        if let Some(manifest) = manifest {
+            cmd.arg("--manifest-path").arg(manifest);
+        }
+
+        let output = cmd.output().with_context(|| "cannot run cargo metadata")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("cargo metadata failed: {stderr}", stderr = stderr.trim())
+        }
+
+        serde_json::from_slice(&output.stdout).with_context(|| "cannot parse cargo metadata")
+    }
+
+    /// The package cargo would use for the manifest, or the current directory
+    ///
+    /// NOTE a virtual manifest doesn't have a package, unless the workspace only has one
+    pub fn package(&self, manifest: Option<&Path>) -> Option<&Package> {
+        if let [package] = &*self.packages {
+            return Some(package);
+        }
+
+        let manifest = match manifest {
+            Some(manifest) => manifest.to_path_buf(),
+            None => std::env::current_dir()
+                .ok()?
+                .ancestors()
+                .map(|dir| dir.join("Cargo.toml"))
+                .find(|path| path.is_file())?,
+        };
+        // cargo's paths are absolute
+        let manifest = manifest.canonicalize().ok()?;
+
+        self.packages
+            .iter()
+            .find(|package| package.manifest_path == manifest)
+    }
+}
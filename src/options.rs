@@ -1,12 +1,27 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{borrow::Cow, cmp::Ordering, collections::HashSet, str::FromStr};
 
-use crate::parse::Level;
+use clap::{builder::PossibleValue, ValueEnum};
+
+use crate::{
+    parse::{Level, Message},
+    Hyperlinks, Templates,
+};
+
+mod expr;
+pub use expr::Expression;
 
 #[derive(Default, Clone, Debug)]
 pub struct RenderOptions {
     pub render: RenderStyle,
     pub include_notes: IncludeNotes,
     pub filter: HashSet<Filter>,
+    pub only: HashSet<Only>,
+    pub remap: Vec<Remap>,
+    pub hyperlinks: Hyperlinks,
+    /// Only use ASCII, mark highlights with carets and always spell out the level
+    pub ascii: bool,
+    pub templates: Templates,
+    pub format: Format,
 }
 
 impl RenderOptions {
@@ -29,26 +44,44 @@ impl RenderOptions {
         self
     }
 
-    pub fn is_ignored(&self, level: Level, name: Option<&str>) -> bool {
-        self.filter.iter().any(|f| match f {
-            Filter::Error(lint) if matches!(level, Level::Error) => {
-                if let Some(name) = name {
-                    lint.eq_ignore_ascii_case(name)
-                } else {
-                    false
-                }
-            }
-            Filter::Warning(lint) if matches!(level, Level::Warning) => {
-                if let Some(name) = name {
-                    lint.eq_ignore_ascii_case(name)
-                } else {
-                    false
-                }
-            }
-            Filter::AllErrors if matches!(level, Level::Error) => true,
-            Filter::AllWarnings if matches!(level, Level::Warning) => true,
-            _ => false,
-        })
+    pub fn without_matching(mut self, expr: Expression) -> Self {
+        self.filter.insert(Filter::Expr(expr));
+        self
+    }
+
+    pub fn only(mut self, only: Only) -> Self {
+        self.only.insert(only);
+        self
+    }
+
+    pub fn only_lint(self, name: impl ToString) -> Self {
+        self.only(Only::Lint(name.to_string()))
+    }
+
+    pub fn remap(mut self, remap: Remap) -> Self {
+        self.remap.push(remap);
+        self
+    }
+
+    /// Changes the level of the message using the first matching rule
+    ///
+    /// This should happen before `is_ignored` so the filters see the new level
+    pub fn remap_level(&self, message: &mut Message) {
+        let Some(remap) = self.remap.iter().find(|r| r.matches(message)) else {
+            return;
+        };
+
+        message.original_level.get_or_insert(message.level);
+        message.level = remap.level;
+    }
+
+    pub fn is_ignored(&self, message: &Message) -> bool {
+        // errors without a code aren't lints, so an allow-list shouldn't hide them
+        let allowed = self.only.is_empty()
+            || (matches!(message.level, Level::Error) && message.code.is_none())
+            || self.only.iter().any(|o| o.matches(message));
+
+        !allowed || self.filter.iter().any(|f| f.matches(message))
     }
 }
 
@@ -59,6 +92,52 @@ pub enum RenderStyle {
     Full,
 }
 
+/// How each diagnostic is written
+#[derive(Copy, Clone, Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Format {
+    #[default]
+    Human,
+    Line,
+}
+
+impl ValueEnum for Format {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Human, Self::Line]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Human => PossibleValue::new("human").help("the default, compact output"),
+            Self::Line => PossibleValue::new("line")
+                .help("`path:line:col: level[lint]: message`, for editors and quickfix lists"),
+        })
+    }
+}
+
+/// What is written to stdout
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub enum Emit {
+    #[default]
+    Rendered,
+    CargoJson,
+}
+
+impl ValueEnum for Emit {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Rendered, Self::CargoJson]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Rendered => {
+                PossibleValue::new("rendered").help("the diagnostics, using --format")
+            }
+            Self::CargoJson => PossibleValue::new("cargo-json")
+                .help("the messages from cargo that weren't filtered out, as json"),
+        })
+    }
+}
+
 #[derive(Default, Copy, Clone, Debug)]
 pub enum IncludeNotes {
     Yes,
@@ -72,6 +151,37 @@ pub enum Filter {
     AllErrors,
     Error(String),
     Warning(String),
+    Expr(Expression),
+}
+
+impl Filter {
+    pub fn matches(&self, message: &Message) -> bool {
+        let name = message.code.as_ref().map(|c| &*c.code);
+        match self {
+            Self::Error(lint) if matches!(message.level, Level::Error) => {
+                name.is_some_and(|name| lint.eq_ignore_ascii_case(name))
+            }
+            Self::Warning(lint) if matches!(message.level, Level::Warning) => {
+                name.is_some_and(|name| lint.eq_ignore_ascii_case(name))
+            }
+            Self::AllErrors if matches!(message.level, Level::Error) => true,
+            Self::AllWarnings if matches!(message.level, Level::Warning) => true,
+            Self::Expr(expr) => expr.matches(message),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AllWarnings => f.write_str("warning=all"),
+            Self::AllErrors => f.write_str("error=all"),
+            Self::Error(lint) => write!(f, "error={lint}"),
+            Self::Warning(lint) => write!(f, "warning={lint}"),
+            Self::Expr(expr) => f.write_str(expr.as_str()),
+        }
+    }
 }
 
 impl FromStr for Filter {
@@ -86,30 +196,290 @@ impl FromStr for Filter {
             ));
         }
 
+        let is_lint = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':'))
+        };
+
+        // the simple forms are kept as-is so they can use the `without_*` builders
         match input.split_once('=') {
-            Some(("warning", "all")) => Ok(Self::AllWarnings),
-            Some(("error", "all")) => Ok(Self::AllErrors),
-            Some(("warning", tail)) if !tail.is_empty() => Ok(Self::Warning(tail.to_string())),
-            Some(("error", tail)) if !tail.is_empty() => Ok(Self::Error(tail.to_string())),
+            Some(("warning", "all")) => return Ok(Self::AllWarnings),
+            Some(("error", "all")) => return Ok(Self::AllErrors),
+            Some(("warning", tail)) if is_lint(tail) => return Ok(Self::Warning(tail.to_string())),
+            Some(("error", tail)) if is_lint(tail) => return Ok(Self::Error(tail.to_string())),
+            _ => {}
+        }
 
-            Some((level, tail)) if !level.is_empty() && !tail.is_empty() => Err(Self::Err::raw(
-                clap::error::ErrorKind::ValueValidation,
-                format!("unknown lint/level: {level}={tail}"),
-            )),
+        Expression::parse(input)
+            .map(Self::Expr)
+            .map_err(|err| Self::Err::raw(clap::error::ErrorKind::ValueValidation, err))
+    }
+}
 
-            Some((level, tail)) if level.is_empty() => Err(Self::Err::raw(
-                clap::error::ErrorKind::ValueValidation,
-                format!("\n{{level}}={tail}: level cannot be empty"),
-            )),
-            Some((level, tail)) if tail.is_empty() => Err(Self::Err::raw(
-                clap::error::ErrorKind::ValueValidation,
-                format!("\n{level}={{lint}}: lint cannot be empty"),
-            )),
+impl<'de> serde::Deserialize<'de> for Filter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        <Cow<'_, str>>::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl serde::Serialize for Filter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Only {
+    Lint(String),
+    Group(String),
+}
+
+impl Only {
+    pub fn matches(&self, message: &Message) -> bool {
+        let Some(name) = message.code.as_ref().map(|c| &*c.code) else {
+            return false;
+        };
 
-            _ => Err(Self::Err::raw(
+        match self {
+            Self::Lint(lint) => lint.replace('-', "_").eq_ignore_ascii_case(name),
+            Self::Group(group) => crate::lints::in_group(group, name),
+        }
+    }
+}
+
+impl std::fmt::Display for Only {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lint(name) | Self::Group(name) => f.write_str(name),
+        }
+    }
+}
+
+impl FromStr for Only {
+    type Err = clap::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(Self::Err::raw(
                 clap::error::ErrorKind::ValueValidation,
-                format!("\nexpected one of: warning | error.\ngot a lint: {input}"),
-            )),
+                "input cannot be empty",
+            ));
+        }
+
+        if crate::lints::is_group(input) {
+            return Ok(Self::Group(input.to_string()));
+        }
+        Ok(Self::Lint(input.to_string()))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Only {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        <Cow<'_, str>>::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl serde::Serialize for Only {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Remap {
+    pub lint: Only,
+    pub level: Level,
+    /// Only remap when building with this profile, see [`cargo_profile`](crate::cargo_profile)
+    pub profile: Option<String>,
+}
+
+impl Remap {
+    pub fn matches(&self, message: &Message) -> bool {
+        self.lint.matches(message)
+    }
+
+    pub fn applies_to(&self, profile: &str) -> bool {
+        self.profile.as_deref().is_none_or(|p| p == profile)
+    }
+}
+
+impl std::fmt::Display for Remap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{lint}={level}",
+            lint = self.lint,
+            level = self.level.as_str()
+        )
+    }
+}
+
+impl FromStr for Remap {
+    type Err = clap::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let err = |msg: String| Self::Err::raw(clap::error::ErrorKind::ValueValidation, msg);
+
+        let Some((lint, level)) = input.trim().split_once('=') else {
+            return Err(err(format!(
+                "\nexpected: {{lint}}={{level}}.\ngot: {input}"
+            )));
+        };
+
+        let lint = match lint.trim() {
+            "" => return Err(err(format!("\n{{lint}}={level}: lint cannot be empty"))),
+            lint => lint.parse()?,
+        };
+
+        let level = level.parse().map_err(err)?;
+        Ok(Self {
+            lint,
+            level,
+            profile: None,
+        })
+    }
+}
+
+// the configuration takes `"lint=level"`, or a table when it's only for a profile
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum RemapRepr {
+    Rule(String),
+    Table {
+        lint: String,
+        level: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        profile: Option<String>,
+    },
+}
+
+impl<'de> serde::Deserialize<'de> for Remap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        match RemapRepr::deserialize(deserializer)? {
+            RemapRepr::Rule(rule) => rule.parse().map_err(D::Error::custom),
+            RemapRepr::Table {
+                lint,
+                level,
+                profile,
+            } => {
+                let remap = format!("{lint}={level}").parse::<Self>();
+                let remap = remap.map_err(D::Error::custom)?;
+                Ok(Self { profile, ..remap })
+            }
+        }
+    }
+}
+
+impl serde::Serialize for Remap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.profile {
+            Some(profile) => RemapRepr::Table {
+                lint: self.lint.to_string(),
+                level: self.level.as_str().to_string(),
+                profile: Some(profile.clone()),
+            },
+            None => RemapRepr::Rule(self.to_string()),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SortBy {
+    Severity,
+    File,
+    Line,
+    Lint,
+}
+
+impl SortBy {
+    /// Compares by each key in order, falling back to the next one on a tie
+    pub fn compare_all(keys: &[Self], left: &Message, right: &Message) -> Ordering {
+        keys.iter().fold(Ordering::Equal, |ord, key| {
+            ord.then_with(|| key.compare(left, right))
+        })
+    }
+
+    pub fn compare(self, left: &Message, right: &Message) -> Ordering {
+        fn severity(level: Level) -> u8 {
+            match level {
+                Level::Error => 0,
+                Level::Warning => 1,
+                Level::Note => 2,
+                Level::Help => 3,
+                Level::FailureNote => 4,
+                Level::Unknown => 5,
+            }
+        }
+
+        // messages without a span (or a code) go last
+        fn location(message: &Message) -> Option<(&str, usize, usize)> {
+            let span = message.spans.first()?;
+            Some((&span.file_name, span.line_start, span.column_start))
+        }
+
+        fn some_first<T: Ord>(left: Option<T>, right: Option<T>) -> Ordering {
+            match (left, right) {
+                (Some(left), Some(right)) => left.cmp(&right),
+                (Some(..), None) => Ordering::Less,
+                (None, Some(..)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+
+        match self {
+            Self::Severity => severity(left.level).cmp(&severity(right.level)),
+            Self::File => some_first(
+                location(left).map(|(file, ..)| file),
+                location(right).map(|(file, ..)| file),
+            ),
+            Self::Line => some_first(location(left), location(right)),
+            Self::Lint => some_first(
+                left.code.as_ref().map(|c| &c.code),
+                right.code.as_ref().map(|c| &c.code),
+            ),
         }
     }
 }
+
+impl ValueEnum for SortBy {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Severity, Self::File, Self::Line, Self::Lint]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Severity => PossibleValue::new("severity").help("errors first, then warnings"),
+            Self::File => PossibleValue::new("file").help("by file name"),
+            Self::Line => PossibleValue::new("line").help("by file name, line and column"),
+            Self::Lint => PossibleValue::new("lint").help("by lint name"),
+        })
+    }
+}
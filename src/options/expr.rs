@@ -0,0 +1,487 @@
+use std::hash::{Hash, Hasher};
+
+use crate::parse::Message;
+
+/// A parsed filter expression, e.g. `level=warning and not path~"tests/**"`
+#[derive(Clone, Debug)]
+pub struct Expression {
+    source: String,
+    expr: Expr,
+}
+
+impl Expression {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser { input, pos: 0 };
+        let expr = parser.expr()?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error("unexpected input"));
+        }
+
+        Ok(Self {
+            source: input.to_string(),
+            expr,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn matches(&self, message: &Message) -> bool {
+        self.expr.matches(message)
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Expression {}
+
+impl Hash for Expression {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Or(Box<Self>, Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Match(Field, Matcher),
+}
+
+impl Expr {
+    fn matches(&self, message: &Message) -> bool {
+        match self {
+            Self::Or(left, right) => left.matches(message) || right.matches(message),
+            Self::And(left, right) => left.matches(message) && right.matches(message),
+            Self::Not(expr) => !expr.matches(message),
+            Self::Match(field, matcher) => field.matches(message, matcher),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Field {
+    Level,
+    Lint,
+    Path,
+    Message,
+}
+
+impl Field {
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("level", Self::Level),
+        ("lint", Self::Lint),
+        ("code", Self::Lint),
+        ("path", Self::Path),
+        ("file", Self::Path),
+        ("message", Self::Message),
+    ];
+
+    fn matches(self, message: &Message, matcher: &Matcher) -> bool {
+        match self {
+            Self::Level => matcher.matches(message.level.as_str(), true),
+            Self::Lint => message
+                .code
+                .as_ref()
+                .is_some_and(|code| matcher.matches(&code.code, true)),
+            // windows paths are normalized so `tests/**` works everywhere
+            Self::Path => message
+                .spans
+                .iter()
+                .any(|span| matcher.matches(&span.file_name.replace('\\', "/"), false)),
+            Self::Message => matcher.matches(&message.message, false),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Exact(String),
+    Glob(String),
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    fn matches(&self, input: &str, ignore_case: bool) -> bool {
+        match self {
+            Self::Exact(s) if ignore_case => s.eq_ignore_ascii_case(input),
+            Self::Exact(s) => s == input,
+            Self::Glob(pattern) if ignore_case => {
+                glob_match(&pattern.to_ascii_lowercase(), &input.to_ascii_lowercase())
+            }
+            Self::Glob(pattern) => glob_match(pattern, input),
+            Self::Regex(re) => re.is_match(input),
+        }
+    }
+}
+
+/// `*` matches within a path segment, `**` matches across them and `?` matches a single character
+fn glob_match(pattern: &str, input: &str) -> bool {
+    fn matches(pattern: &[char], input: &[char]) -> bool {
+        match pattern {
+            [] => input.is_empty(),
+            ['*', '*', '/', rest @ ..] => (0..=input.len())
+                .filter(|&i| i == 0 || input[i - 1] == '/')
+                .any(|i| matches(rest, &input[i..])),
+            ['*', '*', rest @ ..] => (0..=input.len()).any(|i| matches(rest, &input[i..])),
+            ['*', rest @ ..] => (0..=input.len())
+                .take_while(|&i| i == 0 || input[i - 1] != '/')
+                .any(|i| matches(rest, &input[i..])),
+            ['?', rest @ ..] => matches!(input, [c, ..] if *c != '/') && matches(rest, &input[1..]),
+            [p, rest @ ..] => matches!(input, [c, ..] if c == p) && matches(rest, &input[1..]),
+        }
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let input = input.chars().collect::<Vec<_>>();
+    matches(&pattern, &input)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            let right = self.and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.keyword("and") {
+            let right = self.unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.keyword("not") {
+            return self.unary().map(|expr| Expr::Not(Box::new(expr)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        self.skip_whitespace();
+        if self.eat("(") {
+            let expr = self.expr()?;
+            self.skip_whitespace();
+            if !self.eat(")") {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(expr);
+        }
+
+        let start = self.pos;
+        let name = self.take_while(|c| c.is_ascii_alphabetic() || c == '_');
+        if name.is_empty() {
+            return Err(self.error("expected a field"));
+        }
+
+        self.skip_whitespace();
+        let (negate, glob) = if self.eat("!=") {
+            (true, false)
+        } else if self.eat("!~") {
+            (true, true)
+        } else if self.eat("=") {
+            (false, false)
+        } else if self.eat("~") {
+            (false, true)
+        } else {
+            return Err(self.error("expected one of: = != ~ !~"));
+        };
+
+        // `warning=unused_imports` and `error=all` are shorthands for level (and lint) matches
+        if let ("warning" | "error", false, false) = (name, negate, glob) {
+            let level = Expr::Match(Field::Level, Matcher::Exact(name.to_string()));
+            return match self.value()? {
+                lint if lint.eq_ignore_ascii_case("all") => Ok(level),
+                lint => Ok(Expr::And(
+                    Box::new(level),
+                    Box::new(Expr::Match(Field::Lint, Matcher::Exact(lint))),
+                )),
+            };
+        }
+
+        let Some(field) = Field::NAMES
+            .iter()
+            .find_map(|&(key, field)| key.eq_ignore_ascii_case(name).then_some(field))
+        else {
+            self.pos = start;
+            return Err(self.error(format!(
+                "unknown field `{name}`, expected one of: level | lint | code | path | message"
+            )));
+        };
+
+        self.skip_whitespace();
+        let matcher = if glob && self.peek() == Some('/') {
+            let start = self.pos;
+            let pattern = self.regex()?;
+            regex::Regex::new(&pattern)
+                .map(Matcher::Regex)
+                .map_err(|err| {
+                    self.pos = start;
+                    self.error(format!("invalid regex: {err}"))
+                })?
+        } else if glob {
+            Matcher::Glob(self.value()?)
+        } else {
+            Matcher::Exact(self.value()?)
+        };
+
+        let expr = Expr::Match(field, matcher);
+        Ok(if negate {
+            Expr::Not(Box::new(expr))
+        } else {
+            expr
+        })
+    }
+
+    fn value(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        if self.peek() == Some('"') {
+            return self.delimited('"');
+        }
+
+        let value = self.take_while(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"'));
+        if value.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        Ok(value.to_string())
+    }
+
+    fn regex(&mut self) -> Result<String, String> {
+        self.delimited('/')
+    }
+
+    fn delimited(&mut self, delim: char) -> Result<String, String> {
+        let start = self.pos;
+        self.pos += delim.len_utf8();
+
+        let mut out = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, next)) if next == delim => out.push(next),
+                    // regexes need their escapes
+                    Some((_, next)) if delim == '/' => {
+                        out.push(c);
+                        out.push(next)
+                    }
+                    Some((_, next)) => out.push(next),
+                    None => break,
+                },
+                c if c == delim => {
+                    self.pos += i + c.len_utf8();
+                    return Ok(out);
+                }
+                c => out.push(c),
+            }
+        }
+
+        self.pos = start;
+        Err(self.error(format!("unterminated `{delim}`")))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let matched = rest
+            .get(..keyword.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(keyword))
+            && rest[keyword.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || c == '(');
+
+        if matched {
+            self.pos += keyword.len();
+        }
+        matched
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let matched = self.input[self.pos..].starts_with(s);
+        if matched {
+            self.pos += s.len();
+        }
+        matched
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn take_while(&mut self, mut f: impl FnMut(char) -> bool) -> &'a str {
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn error(&self, msg: impl std::fmt::Display) -> String {
+        let col = self.input[..self.pos].chars().count();
+        format!(
+            "\n{input}\n{pad}^ {msg}",
+            input = self.input,
+            pad = " ".repeat(col)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(level: &str, lint: &str, path: &str, text: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "message": text,
+            "code": { "code": lint },
+            "level": level,
+            "spans": [{
+                "file_name": path,
+                "line_start": 1,
+                "column_start": 1,
+                "text": [],
+            }],
+            "children": [],
+        }))
+        .unwrap()
+    }
+
+    fn matches(expr: &str, message: &Message) -> bool {
+        Expression::parse(expr).unwrap().matches(message)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let expr = "level=warning or level=error and lint=dead_code";
+        assert!(matches(expr, &message("warning", "unused", "a.rs", "")));
+        assert!(matches(expr, &message("error", "dead_code", "a.rs", "")));
+        assert!(!matches(expr, &message("error", "unused", "a.rs", "")));
+    }
+
+    #[test]
+    fn parentheses() {
+        let expr = "(level=warning or level=error) and lint=dead_code";
+        assert!(!matches(expr, &message("warning", "unused", "a.rs", "")));
+        assert!(matches(expr, &message("warning", "dead_code", "a.rs", "")));
+        assert!(matches(expr, &message("error", "dead_code", "a.rs", "")));
+    }
+
+    #[test]
+    fn negation() {
+        let warning = message("warning", "dead_code", "a.rs", "");
+        assert!(!matches("not level=warning", &warning));
+        assert!(matches("not not level=warning", &warning));
+        assert!(matches("not (level=error or lint=unused)", &warning));
+        assert!(matches("lint!=unused", &warning));
+        assert!(!matches("path!~*.rs", &warning));
+        // `not` only applies to the next term
+        assert!(matches("not level=error and lint=dead_code", &warning));
+    }
+
+    #[test]
+    fn keywords_ignore_case() {
+        let warning = message("warning", "dead_code", "a.rs", "");
+        assert!(matches("NOT level=error AND Lint=DEAD_CODE", &warning));
+    }
+
+    #[test]
+    fn level_shorthands() {
+        let warning = message("warning", "dead_code", "a.rs", "");
+        assert!(matches("warning=dead_code", &warning));
+        assert!(matches("warning=all", &warning));
+        assert!(!matches("error=all", &warning));
+        assert!(!matches("warning=unused", &warning));
+    }
+
+    #[test]
+    fn quoted_globs() {
+        let message = message("warning", "dead_code", "src dir/a/b.rs", r#"unused "x""#);
+        assert!(matches(r#"path~"src dir/**""#, &message));
+        assert!(matches(r#"path~"src dir/**/b.rs""#, &message));
+        assert!(matches(r#"path~"src dir/?/*.rs""#, &message));
+        // `*` stays within a segment
+        assert!(!matches(r#"path~"src dir/*.rs""#, &message));
+        assert!(matches(r#"message="unused \"x\"""#, &message));
+        assert!(matches(r#"message~"unused*""#, &message));
+    }
+
+    #[test]
+    fn windows_paths() {
+        let message = message("warning", "dead_code", r"tests\ui\a.rs", "");
+        assert!(matches("path~tests/**", &message));
+    }
+
+    #[test]
+    fn regexes() {
+        let message = message("warning", "dead_code", "a.rs", "unused variable: `x`");
+        assert!(matches(r"message~/^unused \w+/", &message));
+        assert!(!matches(r"message!~/variable/", &message));
+        assert!(matches(r"message~/a\/?.*x/", &message));
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(!glob_match("src/*.rs", "src/a/lib.rs"));
+        assert!(glob_match("src/**/*.rs", "src/lib.rs"));
+        assert!(glob_match("src/**/*.rs", "src/a/b/lib.rs"));
+        assert!(glob_match("**", "a/b"));
+        assert!(!glob_match("?", "/"));
+        assert!(!glob_match("src/**/lib.rs", "src/xlib.rs"));
+    }
+
+    fn error(expr: &str) -> String {
+        Expression::parse(expr).unwrap_err()
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(
+            error("level=warning and"),
+            "\nlevel=warning and\n                 ^ expected a field"
+        );
+        assert_eq!(
+            error("lint=x or lvl=warning"),
+            "\nlint=x or lvl=warning\n          ^ unknown field `lvl`, expected one of: level | lint | code | path | message"
+        );
+        assert_eq!(
+            error("(level=warning"),
+            "\n(level=warning\n              ^ expected `)`"
+        );
+        assert_eq!(
+            error(r#"message="abc"#),
+            "\nmessage=\"abc\n        ^ unterminated `\"`"
+        );
+        assert_eq!(
+            error("level warning"),
+            "\nlevel warning\n      ^ expected one of: = != ~ !~"
+        );
+        assert_eq!(
+            error("level=warning)"),
+            "\nlevel=warning)\n             ^ unexpected input"
+        );
+        assert!(error("message~/(/").starts_with("\nmessage~/(/\n        ^ invalid regex: "));
+    }
+}
@@ -1,9 +1,23 @@
 pub fn gather_reasons(output: impl std::io::Read) -> Vec<Reason> {
+    iter_reasons(output).collect::<_>()
+}
+
+/// Lazily parses reasons as the output is produced
+pub fn iter_reasons(output: impl std::io::Read) -> impl Iterator<Item = Reason> {
     serde_json::Deserializer::from_reader(output)
         .into_iter()
         .flatten()
         .filter(Reason::is_not_empty)
-        .collect::<_>()
+}
+
+/// Lazily parses every reason, keeping the original json alongside it
+pub fn iter_raw_reasons(
+    output: impl std::io::Read,
+) -> impl Iterator<Item = (Reason, Box<serde_json::value::RawValue>)> {
+    serde_json::Deserializer::from_reader(output)
+        .into_iter::<Box<serde_json::value::RawValue>>()
+        .flatten()
+        .filter_map(|raw| Some((serde_json::from_str(raw.get()).ok()?, raw)))
 }
 
 mod reason;
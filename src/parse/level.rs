@@ -9,3 +9,32 @@ pub enum Level {
     #[serde(other)]
     Unknown,
 }
+
+impl Level {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::FailureNote => "failure-note",
+            Self::Help => "help",
+            Self::Note => "note",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for Level {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match &*input.trim().to_ascii_lowercase() {
+            "warning" | "warn" => Ok(Self::Warning),
+            "error" | "deny" => Ok(Self::Error),
+            "note" => Ok(Self::Note),
+            "help" => Ok(Self::Help),
+            level => Err(format!(
+                "unknown level: {level}, expected one of: error | warning | note | help"
+            )),
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use std::borrow::Cow;
 
-use owo_colors::{DynColor, OwoColorize as _};
+use owo_colors::OwoColorize as _;
 
-use crate::{IncludeNotes, RenderOptions, Theme};
+use crate::{
+    hyperlinks::write_link,
+    template::{Placeholder, Value},
+    theme::Style,
+    Hyperlinks, IncludeNotes, RenderOptions, Theme,
+};
 
 use super::{Code, Level, Span};
 
@@ -14,6 +19,12 @@ pub struct Message {
     pub level: Level,
     pub spans: Vec<Span>,
     pub children: Vec<Self>,
+    // the level this message had before it was remapped
+    #[serde(skip)]
+    pub original_level: Option<Level>,
+    // where this message came from when several runs are merged, e.g. `features: a, a+b`
+    #[serde(skip)]
+    pub tags: Vec<String>,
 }
 
 impl Message {
@@ -31,51 +42,234 @@ impl Message {
             _ => theme.unknown,
         };
 
-        let ignored = matches!(self.level, Level::Note)
-            && matches!(render_options.include_notes, IncludeNotes::No);
+        if self.is_hidden(render_options) {
+            return Ok(());
+        }
+        let include_notes = self.include_notes(render_options);
 
-        if !ignored {
-            self.header(color, render_options.include_notes, theme, out)?;
+        if let Some(template) = render_options.templates.header(self.level) {
+            let lookup = |p| self.value(p, None, color, render_options, theme, continuation);
+            template.render(&lookup, out)?;
+            writeln!(out)?;
+        } else {
+            self.header(
+                color,
+                include_notes,
+                &render_options.hyperlinks,
+                render_options.ascii,
+                theme,
+                out,
+            )?;
         }
 
         self.spans.iter().try_for_each(|span| {
-            if ignored {
-                return Ok(());
+            span.render_code(render_options, theme, out)?;
+
+            if let Some(template) = render_options.templates.location(self.level) {
+                let lookup =
+                    |p| self.value(p, Some(span), color, render_options, theme, continuation);
+                template.render(&lookup, out)?;
+                return writeln!(out);
             }
 
-            span.render(render_options, theme, continuation, out)?;
-            if matches!(self.level, Level::Warning) {
+            span.render_location(render_options, theme, continuation, out)?;
+            // errors already show their code in the header
+            if !matches!(self.level, Level::Error) {
                 if let Some(Code { code }) = self.code.as_ref() {
-                    write!(out, "({code})", code = code.color(theme.lint_name))?;
+                    let url = render_options.hyperlinks.lint_url(code);
+                    write!(out, "(")?;
+                    write_link(out, url.as_deref(), theme.lint_name.paint(code))?;
+                    write!(out, ")")?;
                 }
             }
             writeln!(out)
-        })
+        })?;
+
+        if !self.tags.is_empty() {
+            let tags = format!("[{tags}]", tags = self.tags.join("; "));
+            writeln!(out, "   {tags}", tags = theme.note.paint(tags))?;
+        }
+        Ok(())
+    }
+
+    /// Writes `path:line:col: level[lint]: message`, children are included with the notes
+    pub(super) fn render_line(
+        &self,
+        render_options: &RenderOptions,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        if self.is_hidden(render_options) {
+            return Ok(());
+        }
+        // NOTE a remap shouldn't change how many lines a diagnostic takes
+        let include_notes = matches!(render_options.include_notes, IncludeNotes::Yes);
+
+        let location = self.spans.first().map(Span::location);
+        self.write_line(location.as_deref(), out)?;
+
+        if include_notes {
+            for child in &self.children {
+                let child_location = child.spans.first().map(Span::location);
+                child.write_line(child_location.as_deref().or(location.as_deref()), out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_line(
+        &self,
+        location: Option<&str>,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        if let Some(location) = location {
+            write!(out, "{location}: ")?;
+        }
+
+        write!(out, "{level}", level = self.level.as_str())?;
+        if let Some(Code { code }) = &self.code {
+            write!(out, "[{code}]")?;
+        }
+
+        // multi-line messages would be read as separate diagnostics
+        let message = self
+            .message
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(out, ": {message}")?;
+
+        if !self.tags.is_empty() {
+            write!(out, " [{tags}]", tags = self.tags.join("; "))?;
+        }
+        writeln!(out)
+    }
+
+    /// The span the diagnostic is about, the others are labels like `first borrow here`
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.spans
+            .iter()
+            .find(|span| span.is_primary)
+            .or_else(|| self.spans.first())
+    }
+
+    /// Notes are only shown when they are included
+    pub fn is_hidden(&self, render_options: &RenderOptions) -> bool {
+        matches!(self.level, Level::Note)
+            && matches!(self.include_notes(render_options), IncludeNotes::No)
+    }
+
+    // remapped notes were explicitly asked for, so they are always shown
+    fn include_notes(&self, render_options: &RenderOptions) -> IncludeNotes {
+        match self.original_level {
+            Some(..) => IncludeNotes::Yes,
+            None => render_options.include_notes,
+        }
+    }
+
+    // the value of a template placeholder, `span` is only available in the location
+    fn value<'a>(
+        &'a self,
+        placeholder: Placeholder,
+        span: Option<&'a Span>,
+        color: Style,
+        render_options: &RenderOptions,
+        theme: &Theme,
+        continuation: &'a Option<Cow<'static, str>>,
+    ) -> Option<Value<'a>> {
+        let hyperlinks = &render_options.hyperlinks;
+        let location_url = |span: &Span| {
+            hyperlinks.location_url(&span.file_name, span.line_start, span.column_start)
+        };
+        let location = |text: Cow<'a, str>, span: &Span| Value {
+            text,
+            style: theme.location,
+            url: location_url(span),
+        };
+
+        let value = match placeholder {
+            Placeholder::Level => Value {
+                text: Cow::Borrowed(self.level.as_str()),
+                style: color,
+                url: None,
+            },
+            Placeholder::Message => Value {
+                text: Cow::Borrowed(&self.message),
+                style: theme.message.bold(),
+                url: None,
+            },
+            Placeholder::Lint => {
+                let code = &self.code.as_ref()?.code;
+                Value {
+                    text: Cow::Borrowed(code),
+                    style: theme.lint_name,
+                    url: hyperlinks.lint_url(code),
+                }
+            }
+            Placeholder::File => {
+                let span = span?;
+                location(Cow::Borrowed(&span.file_name), span)
+            }
+            Placeholder::Line => {
+                let span = span?;
+                location(Cow::Owned(span.line_start.to_string()), span)
+            }
+            Placeholder::Col => {
+                let span = span?;
+                location(Cow::Owned(span.column_start.to_string()), span)
+            }
+            Placeholder::Location => {
+                let span = span?;
+                location(Cow::Owned(span.location()), span)
+            }
+            Placeholder::Continuation => Value {
+                text: Cow::Borrowed(continuation.as_deref()?),
+                style: theme.continuation,
+                url: None,
+            },
+        };
+        Some(value)
     }
 
     fn header(
         &self,
-        color: impl DynColor,
+        color: Style,
         include_notes: IncludeNotes,
+        hyperlinks: &Hyperlinks,
+        ascii: bool,
         theme: &Theme,
         out: &mut dyn std::io::Write,
     ) -> std::io::Result<()> {
         match self.level {
+            // NOTE the level is spelled out so it doesn't rely on the color
+            Level::Error if ascii && self.code.is_some() => {
+                let code = self.code.as_ref().map(|c| &c.code).unwrap();
+                let url = hyperlinks.lint_url(code);
+                write!(out, "{error}[", error = color.paint("error"))?;
+                write_link(out, url.as_deref(), color.paint(code))?;
+                write!(out, "] ")?;
+            }
             Level::Error if self.code.is_some() => {
-                write!(
-                    out,
-                    "{error} ",
-                    error = self.code.as_ref().map(|c| &c.code).unwrap().color(color)
-                )?;
+                let code = self.code.as_ref().map(|c| &c.code).unwrap();
+                let url = hyperlinks.lint_url(code);
+                write_link(out, url.as_deref(), color.paint(code))?;
+                write!(out, " ")?;
             }
             Level::Error => {
-                write!(out, "{error} ", error = "error".color(color))?;
+                write!(out, "{error} ", error = color.paint("error"))?;
             }
             Level::Warning => {
-                write!(out, "{warning} ", warning = "warning".color(color))?;
+                write!(out, "{warning} ", warning = color.paint("warning"))?;
             }
             Level::Note if matches!(include_notes, IncludeNotes::Yes) => {
-                write!(out, "{note} ", note = "note".color(color))?;
+                write!(out, "{note} ", note = color.paint("note"))?;
+            }
+            Level::Help => {
+                write!(out, "{help} ", help = color.paint("help"))?;
+            }
+            Level::FailureNote | Level::Unknown if ascii => {
+                write!(out, "{level} ", level = color.paint(self.level.as_str()))?;
             }
             _ => {}
         }
@@ -83,7 +277,7 @@ impl Message {
         writeln!(
             out,
             "{message}",
-            message = self.message.color(theme.message).bold()
+            message = theme.message.paint(&self.message).bold()
         )
     }
 }
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::{RenderOptions, Theme};
+use crate::{Format, RenderOptions, Theme};
 
 use super::Message;
 
@@ -26,9 +26,10 @@ impl Reason {
         out: &mut dyn std::io::Write,
     ) -> std::io::Result<()> {
         match self {
-            Self::CompilerMessage { message } => {
-                message.render(render_options, theme, continuation, out)
-            }
+            Self::CompilerMessage { message } => match render_options.format {
+                Format::Human => message.render(render_options, theme, continuation, out),
+                Format::Line => message.render_line(render_options, out),
+            },
             Self::BuildFinished { success: true } => {
                 // TODO perhaps report this with a flag
                 Ok(())
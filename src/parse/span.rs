@@ -1,8 +1,6 @@
 use std::borrow::Cow;
 
-use owo_colors::OwoColorize as _;
-
-use crate::{RenderOptions, RenderStyle, Theme};
+use crate::{hyperlinks::write_link, RenderOptions, RenderStyle, Theme};
 
 use super::Text;
 
@@ -10,20 +8,33 @@ use super::Text;
 pub struct Span {
     pub column_start: usize,
     pub line_start: usize,
+    #[serde(default)]
+    pub column_end: usize,
+    #[serde(default)]
+    pub line_end: usize,
     pub file_name: String,
     pub text: Vec<Text>,
+    #[serde(default)]
+    pub is_primary: bool,
 }
 
 impl Span {
-    pub(super) fn render(
+    pub fn location(&self) -> String {
+        format!(
+            "{file}:{line}:{col}",
+            file = self.file_name,
+            line = self.line_start,
+            col = self.column_start,
+        )
+    }
+
+    pub(super) fn render_code(
         &self,
         render_options: &RenderOptions,
         theme: &Theme,
-        continuation: &Option<Cow<'static, str>>,
         out: &mut dyn std::io::Write,
     ) -> std::io::Result<()> {
         if matches!(render_options.render, RenderStyle::Full) {
-            use owo_colors::OwoColorize as _;
             self.relocate().try_for_each(|(start, end, text)| {
                 let start = floor_char_boundary(text, start);
                 let end = ceil_char_boundary(text, end);
@@ -35,35 +46,58 @@ impl Span {
                 writeln!(
                     out,
                     "  {head}{mid}{tail}",
-                    head = head.color(theme.code),
-                    mid = mid.color(theme.highlight),
-                    tail = tail.color(theme.code)
+                    head = theme.code.paint(head),
+                    mid = theme.highlight.paint(mid),
+                    tail = theme.code.paint(tail)
+                )?;
+
+                if !render_options.ascii || mid.trim().is_empty() {
+                    return Ok(());
+                }
+
+                // tabs are kept so the carets line up with the code
+                let pad = head
+                    .chars()
+                    .map(|c| if c == '\t' { c } else { ' ' })
+                    .collect::<String>();
+                let carets = "^".repeat(mid.chars().count());
+                writeln!(
+                    out,
+                    "  {pad}{carets}",
+                    carets = theme.highlight.paint(carets)
                 )
             })?;
         }
+        Ok(())
+    }
 
-        let location = format!(
-            "{file}:{line}:{col}",
-            file = self.file_name,
-            line = self.line_start,
-            col = self.column_start,
+    pub(super) fn render_location(
+        &self,
+        render_options: &RenderOptions,
+        theme: &Theme,
+        continuation: &Option<Cow<'static, str>>,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let location = self.location();
+        let url = render_options.hyperlinks.location_url(
+            &self.file_name,
+            self.line_start,
+            self.column_start,
         );
 
         match continuation {
             Some(continuation) => {
                 write!(
                     out,
-                    " {cont} {location} ",
-                    cont = continuation.color(theme.continuation),
-                    location = location.color(theme.location)
-                )
+                    " {cont} ",
+                    cont = theme.continuation.paint(continuation)
+                )?;
             }
-            None => write!(
-                out,
-                " {location} ",
-                location = location.color(theme.location)
-            ),
+            None => write!(out, " ")?,
         }
+
+        write_link(out, url.as_deref(), theme.location.paint(location))?;
+        write!(out, " ")
     }
 
     fn relocate(&self) -> impl Iterator<Item = (usize, usize, &str)> + '_ {
@@ -0,0 +1,150 @@
+use crate::Features;
+
+/// How to pick the feature combinations to check, similar to `cargo hack`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Powerset {
+    /// Each feature on its own
+    EachFeature,
+    /// Every combination of features, `depth` limits how many are combined
+    FeaturePowerset { depth: Option<usize> },
+}
+
+/// A combination of features and its label, e.g. `a+b`
+#[derive(Clone, Debug)]
+pub struct FeatureSet {
+    pub label: String,
+    pub features: Features,
+}
+
+impl Powerset {
+    /// The combinations for a package's features, default features are never enabled implicitly
+    ///
+    /// This always includes a run without any features, and one with the default features
+    pub fn feature_sets<'a>(
+        self,
+        features: impl IntoIterator<Item = &'a str>,
+        exclude: &[String],
+    ) -> Vec<FeatureSet> {
+        // `default` is checked on its own, and combining it with others would be redundant
+        let features = features
+            .into_iter()
+            .filter(|&feature| feature != "default" && !exclude.iter().any(|e| e == feature))
+            .collect::<Vec<_>>();
+
+        let mut sets = vec![
+            FeatureSet {
+                label: String::from("no-default"),
+                features: Features::None,
+            },
+            FeatureSet {
+                label: String::from("default"),
+                features: Features::Default,
+            },
+        ];
+
+        let depth = match self {
+            Self::EachFeature => 1,
+            Self::FeaturePowerset { depth } => depth.unwrap_or(features.len()),
+        };
+
+        // subsets in order of their size, so the simpler combinations come first
+        for size in 1..=depth.min(features.len()) {
+            let mut indices = (0..size).collect::<Vec<_>>();
+            loop {
+                let set = indices.iter().map(|&i| features[i]).collect::<Vec<_>>();
+                sets.push(FeatureSet {
+                    label: set.join("+"),
+                    features: Features::Exactly(set.into_iter().map(String::from).collect()),
+                });
+
+                // the next combination, in lexicographic order
+                let Some(pos) = (0..size)
+                    .rev()
+                    .find(|&i| indices[i] < features.len() - size + i)
+                else {
+                    break;
+                };
+                indices[pos] += 1;
+                for i in pos + 1..size {
+                    indices[i] = indices[i - 1] + 1;
+                }
+            }
+        }
+
+        sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(powerset: Powerset, features: &[&str], exclude: &[&str]) -> Vec<String> {
+        let exclude = exclude.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        powerset
+            .feature_sets(features.iter().copied(), &exclude)
+            .into_iter()
+            .map(|set| set.label)
+            .collect()
+    }
+
+    #[test]
+    fn each_feature() {
+        assert_eq!(
+            labels(Powerset::EachFeature, &["default", "a", "b"], &[]),
+            ["no-default", "default", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn powerset_by_size() {
+        let powerset = Powerset::FeaturePowerset { depth: None };
+        assert_eq!(
+            labels(powerset, &["a", "b", "c"], &[]),
+            [
+                "no-default",
+                "default",
+                "a",
+                "b",
+                "c",
+                "a+b",
+                "a+c",
+                "b+c",
+                "a+b+c"
+            ]
+        );
+    }
+
+    #[test]
+    fn powerset_depth() {
+        let powerset = Powerset::FeaturePowerset { depth: Some(2) };
+        assert_eq!(
+            labels(powerset, &["a", "b", "c"], &[]),
+            ["no-default", "default", "a", "b", "c", "a+b", "a+c", "b+c"]
+        );
+
+        let powerset = Powerset::FeaturePowerset { depth: Some(0) };
+        assert_eq!(labels(powerset, &["a"], &[]), ["no-default", "default"]);
+    }
+
+    #[test]
+    fn excluded_features() {
+        let powerset = Powerset::FeaturePowerset { depth: None };
+        assert_eq!(
+            labels(powerset, &["default", "a", "b", "c"], &["b"]),
+            ["no-default", "default", "a", "c", "a+c"]
+        );
+        assert_eq!(
+            labels(Powerset::EachFeature, &[], &[]),
+            ["no-default", "default"]
+        );
+    }
+
+    #[test]
+    fn only_the_set_is_enabled() {
+        let sets = Powerset::EachFeature.feature_sets(["a", "b"], &[]);
+        assert!(matches!(sets[0].features, Features::None));
+        assert!(matches!(sets[1].features, Features::Default));
+        assert!(matches!(&sets[3].features, Features::Exactly(f) if f == &["b"]));
+    }
+}
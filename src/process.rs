@@ -0,0 +1,410 @@
+use std::{
+    io::{BufRead, BufReader, Write as _},
+    process::{Child, ChildStdout, ExitStatus},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+
+use crate::{Level, Message};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether ctrl-c was pressed while cargo was running
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// A running cargo
+///
+/// Its stderr is read on another thread: the status lines (`Checking ..`), the progress bar and
+/// anything that isn't cargo's are passed through, and cargo's own errors and warnings are kept
+/// so they can be shown with the diagnostics.
+/// The child is killed on ctrl-c, or when the timeout is reached
+pub struct Process {
+    child: Arc<Mutex<Reaped>>,
+    stdout: Option<ChildStdout>,
+    stderr: Option<JoinHandle<Vec<String>>>,
+    watchdog: Option<JoinHandle<Option<Stopped>>>,
+    done: Arc<AtomicBool>,
+    timeout: Option<Duration>,
+    killed: bool,
+}
+
+/// What cargo left behind once it exited
+pub struct Finished {
+    /// This is `None` if cargo was stopped by ccs
+    pub status: Option<ExitStatus>,
+    /// Errors and warnings from stderr, e.g. a bad manifest or a build script's `cargo:warning=`
+    pub messages: Vec<Message>,
+    /// cargo's `could not compile` summary, this is only useful when no errors were shown
+    pub summary: Option<Message>,
+}
+
+impl Finished {
+    /// An error for when cargo failed without saying why, e.g. a wrapper that exited early
+    pub fn exit_error(&self, shown_error: bool) -> Option<Message> {
+        let status = self.status.filter(|status| !status.success())?;
+        let explained = shown_error
+            || self
+                .messages
+                .iter()
+                .chain(&self.summary)
+                .any(|message| matches!(message.level, Level::Error));
+        if explained {
+            return None;
+        }
+
+        let text = match status.code() {
+            Some(code) => format!("cargo exited with code {code}"),
+            None => format!("cargo exited with {status}"),
+        };
+        Some(message(Level::Error, text))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Stopped {
+    Interrupted,
+    TimedOut,
+}
+
+impl Process {
+    /// Spawns the command, its `stdout` and `stderr` have to be piped
+    pub fn spawn(
+        mut cmd: std::process::Command,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        // NOTE rustup's proxy and the build scripts would outlive cargo, and keep its output open
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt as _;
+            cmd.process_group(0);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("cannot run {:?}", cmd.get_program()))?;
+        handle_signals(true);
+
+        let stdout = child.stdout.take();
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| std::thread::spawn(move || read_stderr(BufReader::new(stderr))));
+
+        let child = Arc::new(Mutex::new(Reaped {
+            child,
+            reaped: false,
+        }));
+        let done = Arc::new(AtomicBool::new(false));
+        let watchdog = std::thread::spawn({
+            let (child, done) = (Arc::clone(&child), Arc::clone(&done));
+            let start = Instant::now();
+            move || loop {
+                if done.load(Ordering::SeqCst) {
+                    return None;
+                }
+
+                let stopped = if interrupted() {
+                    Some(Stopped::Interrupted)
+                } else if timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+                    Some(Stopped::TimedOut)
+                } else {
+                    None
+                };
+
+                if stopped.is_some() {
+                    child.lock().unwrap().kill();
+                    return stopped;
+                }
+                std::thread::park_timeout(Duration::from_millis(50));
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdout,
+            stderr,
+            watchdog: Some(watchdog),
+            done,
+            timeout,
+            killed: false,
+        })
+    }
+
+    pub fn take_stdout(&mut self) -> ChildStdout {
+        self.stdout
+            .take()
+            .expect("stdout attached to the child process")
+    }
+
+    /// Stops cargo early, e.g. when enough diagnostics have been read
+    pub fn kill(&mut self) {
+        self.killed = true;
+        self.child.lock().unwrap().kill();
+    }
+
+    /// Waits for cargo to exit, and sorts out what it wrote to stderr
+    pub fn finish(mut self) -> Finished {
+        // NOTE the lock isn't held while waiting, so the watchdog can still kill the child.
+        // reaping happens under the same lock, so it can't kill a reused pid afterwards
+        let status = loop {
+            match self.child.lock().unwrap().try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) => {}
+                Err(..) => break None,
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        self.done.store(true, Ordering::SeqCst);
+        let stopped = self.watchdog.take().and_then(|watchdog| {
+            watchdog.thread().unpark();
+            watchdog.join().ok().flatten()
+        });
+
+        let lines = self
+            .stderr
+            .take()
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default();
+        let (mut messages, summary) = classify(&lines);
+
+        if let (Some(Stopped::TimedOut), Some(timeout)) = (stopped, self.timeout) {
+            let secs = timeout.as_secs();
+            messages.push(message(
+                Level::Error,
+                format!("cargo was stopped after the timeout of {secs}s"),
+            ));
+        }
+
+        Finished {
+            status: status.filter(|_| !self.killed && stopped.is_none()),
+            messages,
+            summary,
+        }
+    }
+}
+
+impl Drop for Process {
+    // reaps the child if it wasn't waited on, e.g. when reading its output failed
+    fn drop(&mut self) {
+        if let Some(watchdog) = self.watchdog.take() {
+            self.done.store(true, Ordering::SeqCst);
+            watchdog.thread().unpark();
+            let mut child = self.child.lock().unwrap();
+            child.kill();
+            child.wait();
+        }
+        handle_signals(false);
+    }
+}
+
+// the child, and whether it has been waited on. its pid can be reused once it has been
+struct Reaped {
+    child: Child,
+    reaped: bool,
+}
+
+impl Reaped {
+    fn try_wait(&mut self) -> std::io::Result<Option<ExitStatus>> {
+        let status = self.child.try_wait()?;
+        self.reaped |= status.is_some();
+        Ok(status)
+    }
+
+    fn wait(&mut self) {
+        let _ = self.child.wait();
+        self.reaped = true;
+    }
+
+    // kills cargo and everything it started
+    fn kill(&mut self) {
+        if self.reaped {
+            return;
+        }
+        #[cfg(unix)]
+        // SAFETY: the child leads its own process group, and hasn't been reaped yet
+        unsafe {
+            libc::kill(-(self.child.id() as libc::pid_t), libc::SIGKILL);
+        }
+        let _ = self.child.kill();
+    }
+}
+
+// passes the status lines through as they arrive, and collects the messages
+fn read_stderr(mut reader: impl BufRead) -> Vec<String> {
+    let mut out = std::io::stderr();
+    let mut lines = vec![];
+    let mut in_message = false;
+
+    let mut handle = |mut raw: &[u8]| {
+        // cargo clears its progress bar before writing anything else
+        if let Some(rest) = raw.strip_prefix(b"\x1b[K") {
+            let _ = out.write_all(b"\x1b[K");
+            raw = rest;
+        }
+
+        let line = strip_ansi(&String::from_utf8_lossy(raw));
+        let line = line.trim_end_matches(['\r', '\n']);
+        if is_status(line) {
+            in_message = false;
+        } else if message_start(line).is_some() {
+            in_message = true;
+        }
+
+        if in_message {
+            lines.push(line.to_string());
+        } else {
+            // NOTE anything else isn't cargo's, e.g. a wrapper's output
+            let _ = out.write_all(raw);
+        }
+    };
+
+    // NOTE the progress bar is redrawn after a `\r`, so that ends a line as well
+    let mut line = vec![];
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok([]) | Err(..) => break,
+            Ok(buf) => buf,
+        };
+        let (len, end) = match buf.iter().position(|&b| b == b'\n' || b == b'\r') {
+            Some(pos) => (pos + 1, true),
+            None => (buf.len(), false),
+        };
+        line.extend_from_slice(&buf[..len]);
+        reader.consume(len);
+        if end {
+            handle(&line);
+            line.clear();
+        }
+    }
+    if !line.is_empty() {
+        handle(&line);
+    }
+
+    lines
+}
+
+// removes the color codes, cargo colors its output when it's told to
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.clone().next() == Some('[') {
+            // parameters and intermediates, up to the final byte
+            chars.by_ref().skip(1).find(|c| ('@'..='~').contains(c));
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// cargo's messages start with their level, e.g. `error: failed to parse manifest`
+fn message_start(line: &str) -> Option<(Level, &str)> {
+    [
+        ("error: ", Level::Error),
+        ("warning: ", Level::Warning),
+        ("note: ", Level::Note),
+        ("help: ", Level::Help),
+    ]
+    .into_iter()
+    .find_map(|(prefix, level)| Some((level, line.strip_prefix(prefix)?)))
+}
+
+// cargo's status lines are right-aligned to 12 columns, e.g. `   Compiling ccs v0.9.2`
+fn is_status(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    trimmed.split_once(' ').is_some_and(|(word, _)| {
+        indent > 0
+            && indent + word.len() == 12
+            && word.starts_with(|c: char| c.is_ascii_uppercase())
+            && word.chars().all(|c| c.is_ascii_alphabetic())
+    })
+}
+
+// groups the lines into messages, each `level: ..` line starts a new one
+fn classify(lines: &[String]) -> (Vec<Message>, Option<Message>) {
+    let mut messages = vec![];
+    let mut summary = None;
+    let mut current: Option<(Level, Vec<&str>)> = None;
+
+    let mut flush = |current: Option<(Level, Vec<&str>)>| {
+        let Some((level, lines)) = current else {
+            return;
+        };
+        let text = lines.join("\n").trim_end().to_string();
+
+        // cargo repeats what the diagnostics already said
+        if text.contains(") generated ") || text.starts_with("build failed, waiting for") {
+            return;
+        }
+        if text.starts_with("could not compile") {
+            summary.get_or_insert_with(|| message(level, text));
+            return;
+        }
+        messages.push(message(level, text));
+    };
+
+    // NOTE the reader only keeps the lines of messages, so the first one starts a message
+    for line in lines {
+        if let Some((level, rest)) = message_start(line) {
+            flush(current.replace((level, vec![rest])));
+        } else if let Some((_, lines)) = &mut current {
+            lines.push(line);
+        }
+    }
+    flush(current);
+
+    (messages, summary)
+}
+
+fn message(level: Level, message: String) -> Message {
+    Message {
+        code: None,
+        message,
+        level,
+        spans: vec![],
+        children: vec![],
+        original_level: None,
+        tags: vec![],
+    }
+}
+
+// the handlers are only installed while cargo runs, otherwise ctrl-c (or an editor's SIGTERM)
+// stops ccs as usual
+fn handle_signals(running: bool) {
+    static RUNNING: Mutex<usize> = Mutex::new(0);
+    let mut count = RUNNING.lock().unwrap();
+    let before = *count;
+    *count = if running { before + 1 } else { before - 1 };
+    // only the first process installs them, and the last one restores the default
+    if before.min(*count) != 0 {
+        return;
+    }
+
+    #[cfg(unix)]
+    {
+        extern "C" fn handle(_: libc::c_int) {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+        }
+        let handler = if running {
+            handle as extern "C" fn(libc::c_int) as libc::sighandler_t
+        } else {
+            libc::SIG_DFL
+        };
+        // SAFETY: the handler only stores to an atomic
+        unsafe {
+            libc::signal(libc::SIGINT, handler);
+            libc::signal(libc::SIGTERM, handler);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use std::{borrow::Cow, str::FromStr};
+
+use crate::{hyperlinks::write_link, Level, Style};
+
+/// Templates for the header and location lines, optionally per level
+///
+/// ```toml
+/// [templates]
+/// header = "{level}: {message}"
+/// location = "  at {file}:{line}:{col}{? [{lint}]}"
+/// error.header = "error[{lint}]: {message}"
+/// ```
+#[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Templates {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Template>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Template>,
+    #[serde(skip_serializing_if = "LevelTemplates::is_empty")]
+    pub warning: LevelTemplates,
+    #[serde(skip_serializing_if = "LevelTemplates::is_empty")]
+    pub error: LevelTemplates,
+    #[serde(skip_serializing_if = "LevelTemplates::is_empty")]
+    pub note: LevelTemplates,
+    #[serde(skip_serializing_if = "LevelTemplates::is_empty")]
+    pub help: LevelTemplates,
+}
+
+#[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LevelTemplates {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Template>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Template>,
+}
+
+impl LevelTemplates {
+    pub const fn is_empty(&self) -> bool {
+        self.header.is_none() && self.location.is_none()
+    }
+}
+
+impl Templates {
+    pub fn header(&self, level: Level) -> Option<&Template> {
+        self.for_level(level)
+            .and_then(|t| t.header.as_ref())
+            .or(self.header.as_ref())
+    }
+
+    pub fn location(&self, level: Level) -> Option<&Template> {
+        self.for_level(level)
+            .and_then(|t| t.location.as_ref())
+            .or(self.location.as_ref())
+    }
+
+    const fn for_level(&self, level: Level) -> Option<&LevelTemplates> {
+        match level {
+            Level::Warning => Some(&self.warning),
+            Level::Error => Some(&self.error),
+            Level::Note => Some(&self.note),
+            Level::Help => Some(&self.help),
+            Level::FailureNote | Level::Unknown => None,
+        }
+    }
+}
+
+/// A value for a placeholder, painted with `style` and linked to `url`
+pub struct Value<'a> {
+    pub text: Cow<'a, str>,
+    pub style: Style,
+    pub url: Option<String>,
+}
+
+/// A format string, e.g. `"{level} {message}{? ({lint})}"`
+///
+/// - `{name}` is replaced with a field
+/// - `{? ...}` is only rendered if all of the fields directly in it have a value
+/// - `{{` and `}}` are literal braces
+#[derive(Clone, Debug)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser { input, pos: 0 };
+        let parts = parser.parts(false)?;
+        Ok(Self {
+            source: input.to_string(),
+            parts,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Renders the template, `lookup` returns `None` for fields that don't have a value
+    pub fn render<'a>(
+        &self,
+        lookup: &dyn Fn(Placeholder) -> Option<Value<'a>>,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        render(&self.parts, lookup, out)
+    }
+}
+
+fn render<'a>(
+    parts: &[Part],
+    lookup: &dyn Fn(Placeholder) -> Option<Value<'a>>,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    for part in parts {
+        match part {
+            Part::Literal(s) => write!(out, "{s}")?,
+            Part::Field(placeholder) => {
+                if let Some(Value { text, style, url }) = lookup(*placeholder) {
+                    write_link(out, url.as_deref(), style.paint(text))?;
+                }
+            }
+            Part::Section(parts) => {
+                let available = parts.iter().all(|part| match part {
+                    Part::Field(placeholder) => lookup(*placeholder).is_some(),
+                    _ => true,
+                });
+                if available {
+                    render(parts, lookup, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for Template {
+    type Err = clap::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+            .map_err(|err| clap::Error::raw(clap::error::ErrorKind::ValueValidation, err))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Template {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        let input = <Cow<'_, str>>::deserialize(deserializer)?;
+        Self::parse(&input).map_err(D::Error::custom)
+    }
+}
+
+impl serde::Serialize for Template {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.source.serialize(serializer)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Placeholder {
+    Level,
+    Message,
+    Lint,
+    File,
+    Line,
+    Col,
+    Location,
+    Continuation,
+}
+
+impl Placeholder {
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("level", Self::Level),
+        ("message", Self::Message),
+        ("lint", Self::Lint),
+        ("code", Self::Lint),
+        ("file", Self::File),
+        ("path", Self::File),
+        ("line", Self::Line),
+        ("col", Self::Col),
+        ("column", Self::Col),
+        ("location", Self::Location),
+        ("continuation", Self::Continuation),
+    ];
+}
+
+#[derive(Clone, Debug)]
+enum Part {
+    Literal(String),
+    Field(Placeholder),
+    Section(Vec<Part>),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parts(&mut self, in_section: bool) -> Result<Vec<Part>, String> {
+        let mut parts = vec![];
+        let mut literal = String::new();
+
+        loop {
+            let rest = &self.input[self.pos..];
+            let Some(c) = rest.chars().next() else {
+                if in_section {
+                    return Err(self.error("unterminated `{?`"));
+                }
+                break;
+            };
+
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                literal.push(c);
+                self.pos += 2;
+                continue;
+            }
+
+            match c {
+                '}' if in_section => {
+                    self.pos += 1;
+                    break;
+                }
+                '}' => return Err(self.error("unmatched `}`, use `}}` for a literal brace")),
+                '{' => {
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(self.placeholder()?);
+                }
+                c => {
+                    literal.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(parts)
+    }
+
+    fn placeholder(&mut self) -> Result<Part, String> {
+        let start = self.pos;
+        self.pos += 1;
+
+        if self.input[self.pos..].starts_with('?') {
+            self.pos += 1;
+            return self.parts(true).map(Part::Section);
+        }
+
+        let Some(len) = self.input[self.pos..].find('}') else {
+            self.pos = start;
+            return Err(self.error("unterminated `{`"));
+        };
+
+        let name = self.input[self.pos..self.pos + len].trim();
+        let Some(placeholder) = Placeholder::NAMES
+            .iter()
+            .find_map(|&(key, p)| key.eq_ignore_ascii_case(name).then_some(p))
+        else {
+            return Err(self.error(format!(
+                "unknown field `{name}`, expected one of: \
+                level | message | lint | file | line | col | location | continuation"
+            )));
+        };
+
+        self.pos += len + 1;
+        Ok(Part::Field(placeholder))
+    }
+
+    fn error(&self, msg: impl std::fmt::Display) -> String {
+        let col = self.input[..self.pos].chars().count();
+        format!(
+            "\n{input}\n{pad}^ {msg}",
+            input = self.input,
+            pad = " ".repeat(col)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `lint` only has a value when it's given, and `line` never has one
+    fn render(template: &str, lint: Option<&'static str>) -> String {
+        let lookup = |placeholder| {
+            let text = match placeholder {
+                Placeholder::Level => "warning",
+                Placeholder::Message => "unused variable",
+                Placeholder::File => "src/lib.rs",
+                Placeholder::Lint => lint?,
+                _ => return None,
+            };
+            Some(Value {
+                text: Cow::Borrowed(text),
+                style: Style::default(),
+                url: None,
+            })
+        };
+
+        let mut out = vec![];
+        Template::parse(template)
+            .unwrap()
+            .render(&lookup, &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(
+            render("{level}: {message}", None),
+            "warning: unused variable"
+        );
+        assert_eq!(render("{ PATH } {Code}", Some("x")), "src/lib.rs x");
+        assert_eq!(render("{line}:{message}", None), ":unused variable");
+    }
+
+    #[test]
+    fn literal_braces() {
+        assert_eq!(render("{{{lint}}} }}{{", Some("x")), "{x} }{");
+    }
+
+    #[test]
+    fn sections() {
+        let template = "{message}{? ({lint})}";
+        assert_eq!(render(template, None), "unused variable");
+        assert_eq!(render(template, Some("x")), "unused variable (x)");
+        // only the fields directly in a section decide if it's shown
+        let template = "{? [{lint}{? :{line}}]}";
+        assert_eq!(render(template, None), "");
+        assert_eq!(render(template, Some("x")), " [x]");
+        assert_eq!(render("{?{file}{{}}}", None), "src/lib.rs{}");
+    }
+
+    #[test]
+    fn level_templates() {
+        let templates: Templates = toml::from_str(
+            r#"
+            header = "{message}"
+            error.header = "error: {message}"
+            note.location = "at {location}"
+            "#,
+        )
+        .unwrap();
+
+        let header = |level| templates.header(level).map(Template::as_str);
+        assert_eq!(header(Level::Error), Some("error: {message}"));
+        assert_eq!(header(Level::Warning), Some("{message}"));
+        assert_eq!(header(Level::Unknown), Some("{message}"));
+
+        let location = |level| templates.location(level).map(Template::as_str);
+        assert_eq!(location(Level::Note), Some("at {location}"));
+        assert_eq!(location(Level::Error), None);
+    }
+
+    fn error(template: &str) -> String {
+        Template::parse(template).unwrap_err()
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("{level} {lvl}"),
+            "\n{level} {lvl}\n         ^ unknown field `lvl`, expected one of: \
+            level | message | lint | file | line | col | location | continuation"
+        );
+        assert_eq!(error("{message"), "\n{message\n^ unterminated `{`");
+        assert_eq!(
+            error("{message}{? ({lint})"),
+            "\n{message}{? ({lint})\n                    ^ unterminated `{?`"
+        );
+        assert_eq!(
+            error("{message}}"),
+            "\n{message}}\n         ^ unmatched `}`, use `}}` for a literal brace"
+        );
+    }
+}
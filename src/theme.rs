@@ -1,19 +1,31 @@
-use std::{borrow::Cow, collections::HashMap, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    str::FromStr,
+    sync::OnceLock,
+};
 
-use owo_colors::DynColors;
+use anyhow::Context as _;
+use owo_colors::{AnsiColors, DynColors, XtermColors};
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+use crate::{gather_reasons, IncludeNotes, RenderOptions, RenderStyle};
+
+// NOTE this is a few messages captured from cargo, trimmed down to what we parse
+const SAMPLE: &str = include_str!("theme/sample.jsonl");
+
+#[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Theme {
-    pub warning: Color,
-    pub error: Color,
-    pub note: Color,
-    pub unknown: Color,
-    pub message: Color,
-    pub code: Color,
-    pub highlight: Color,
-    pub continuation: Color,
-    pub location: Color,
-    pub lint_name: Color,
+    pub warning: Style,
+    pub error: Style,
+    pub note: Style,
+    pub unknown: Style,
+    pub message: Style,
+    pub code: Style,
+    pub highlight: Style,
+    pub continuation: Style,
+    pub location: Style,
+    pub lint_name: Style,
 }
 
 impl Default for Theme {
@@ -24,27 +36,113 @@ impl Default for Theme {
 
 impl Theme {
     pub const DEFAULT: Self = Self {
-        warning: Color::BRIGHT_YELLOW,
-        error: Color::BRIGHT_RED,
-        note: Color::BRIGHT_GREEN,
-        unknown: Color::CYAN,
-        code: Color::WHITE,
-        highlight: Color::BRIGHT_BLUE,
-        lint_name: Color::MAGENTA,
-        location: Color::BRIGHT_BLACK,
-        message: Color::BRIGHT_WHITE,
-        continuation: Color::BRIGHT_BLACK,
+        warning: Style::new(Color::BRIGHT_YELLOW),
+        error: Style::new(Color::BRIGHT_RED),
+        note: Style::new(Color::BRIGHT_GREEN),
+        unknown: Style::new(Color::CYAN),
+        code: Style::new(Color::WHITE),
+        highlight: Style::new(Color::BRIGHT_BLUE),
+        lint_name: Style::new(Color::MAGENTA),
+        location: Style::new(Color::BRIGHT_BLACK),
+        message: Style::new(Color::BRIGHT_WHITE),
+        continuation: Style::new(Color::BRIGHT_BLACK),
+    };
+
+    pub const DARK: Self = Self {
+        warning: Style::new(Color::BRIGHT_YELLOW).bold(),
+        error: Style::new(Color::BRIGHT_RED).bold(),
+        note: Style::new(Color::BRIGHT_GREEN),
+        unknown: Style::new(Color::BRIGHT_CYAN),
+        code: Style::new(Color::WHITE),
+        highlight: Style::new(Color::BRIGHT_CYAN),
+        lint_name: Style::new(Color::BRIGHT_MAGENTA),
+        location: Style::new(Color::BRIGHT_BLACK),
+        message: Style::new(Color::BRIGHT_WHITE),
+        continuation: Style::new(Color::BRIGHT_BLACK),
+    };
+
+    pub const LIGHT: Self = Self {
+        warning: Style::new(Color::Indexed(130)).bold(),
+        error: Style::new(Color::RED).bold(),
+        note: Style::new(Color::GREEN),
+        unknown: Style::new(Color::BLUE),
+        code: Style::new(Color::Indexed(238)),
+        highlight: Style::new(Color::BLUE).bold(),
+        lint_name: Style::new(Color::MAGENTA),
+        location: Style::new(Color::Indexed(242)),
+        message: Style::new(Color::BLACK),
+        continuation: Style::new(Color::Indexed(245)),
+    };
+
+    pub const HIGH_CONTRAST: Self = Self {
+        warning: Style::new(Color::BLACK).on(Color::BRIGHT_YELLOW).bold(),
+        error: Style::new(Color::BRIGHT_WHITE).on(Color::RED).bold(),
+        note: Style::new(Color::BRIGHT_GREEN).bold(),
+        unknown: Style::new(Color::BRIGHT_CYAN).bold(),
+        code: Style::new(Color::BRIGHT_WHITE),
+        highlight: Style::new(Color::BRIGHT_CYAN).bold().underline(),
+        lint_name: Style::new(Color::BRIGHT_MAGENTA).bold(),
+        location: Style::new(Color::BRIGHT_WHITE).underline(),
+        message: Style::new(Color::BRIGHT_WHITE),
+        continuation: Style::new(Color::BRIGHT_WHITE),
+    };
+
+    pub const MONOCHROME: Self = Self {
+        warning: Style::PLAIN.bold(),
+        error: Style::PLAIN.bold().underline(),
+        note: Style::PLAIN.italic(),
+        unknown: Style::PLAIN,
+        code: Style::PLAIN,
+        highlight: Style::PLAIN.underline(),
+        lint_name: Style::PLAIN.italic(),
+        location: Style::PLAIN.dim(),
+        message: Style::PLAIN,
+        continuation: Style::PLAIN.dim(),
     };
 
+    /// The built-in themes, usable by name with `--theme`
+    pub const PRESETS: &'static [(&'static str, Self)] = &[
+        ("default", Self::DEFAULT),
+        ("dark", Self::DARK),
+        ("light", Self::LIGHT),
+        ("high-contrast", Self::HIGH_CONTRAST),
+        ("monochrome", Self::MONOCHROME),
+    ];
+
+    pub fn preset(name: &str) -> Option<Self> {
+        Self::PRESETS
+            .iter()
+            .find_map(|(preset, theme)| preset.eq_ignore_ascii_case(name).then_some(*theme))
+    }
+
+    /// Renders some sample diagnostics with this theme
+    pub fn preview(
+        &self,
+        render_options: RenderOptions,
+        continuation: &Option<Cow<'static, str>>,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let render_options = RenderOptions {
+            render: RenderStyle::Full,
+            include_notes: IncludeNotes::Yes,
+            ..render_options
+        };
+
+        for reason in gather_reasons(SAMPLE.as_bytes()) {
+            reason.render(&render_options, self, continuation, out)?;
+        }
+        Ok(())
+    }
+
     pub fn load(mut map: HashMap<String, String>) -> Option<(Self, Report)> {
         fn try_get(
             report: &mut Report,
             map: &mut HashMap<String, String>,
             key: &str,
-            mut set: impl FnMut(Color),
+            mut set: impl FnMut(Style),
         ) {
-            match map.remove(key).map(|c| c.parse::<Color>()) {
-                Some(Ok(color)) => set(color),
+            match map.remove(key).map(|c| c.parse::<Style>()) {
+                Some(Ok(style)) => set(style),
                 Some(Err(err)) => report.invalid_values.push((key.to_string(), err)),
                 _ => {}
             }
@@ -55,10 +153,9 @@ impl Theme {
 
         macro_rules! get {
             ($($ident:ident)*) => {
-                const KNOWN_KEYS: &[&'static str] = &[$(stringify!($ident)),*];
                 $(
-                    try_get(&mut report, &mut map, stringify!($ident), |color| {
-                        this.$ident = color
+                    try_get(&mut report, &mut map, stringify!($ident), |style| {
+                        this.$ident = style
                     });
                 )*
             };
@@ -67,7 +164,9 @@ impl Theme {
         get! {
             warning
             error
+            note
             unknown
+            code
             highlight
             lint_name
             location
@@ -75,18 +174,201 @@ impl Theme {
             continuation
         }
 
-        for key in map.into_keys() {
-            if !KNOWN_KEYS.contains(&&*key) {
-                report.unknown_keys.push(key.to_string());
+        // anything left over wasn't a known key
+        report.unknown_keys.extend(map.into_keys());
+        report.unknown_keys.sort();
+
+        Some((this, report))
+    }
+
+    /// Loads a theme file, a table of `key = "style"`
+    pub fn load_file(path: &Path) -> anyhow::Result<(Self, Report)> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read theme: {}", path.display()))?;
+        let map = toml::from_str(&data)
+            .with_context(|| format!("cannot parse theme: {}", path.display()))?;
+        Ok(Self::load(map).expect("theme should always load"))
+    }
+
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        toml::Value::try_from(self)
+            .ok()
+            .and_then(|value| value.try_into().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// A foreground color, an optional background color and text attributes
+///
+/// This is written like `"bold underline BrightRed on Black"`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub dim: bool,
+}
+
+impl Style {
+    /// No colors or attributes, this uses the terminal's default
+    pub const PLAIN: Self = Self {
+        fg: None,
+        bg: None,
+        bold: false,
+        italic: false,
+        underline: false,
+        dim: false,
+    };
+
+    pub const fn new(fg: Color) -> Self {
+        Self {
+            fg: Some(fg),
+            ..Self::PLAIN
+        }
+    }
+
+    pub const fn on(mut self, bg: Color) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub const fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    pub fn paint<T>(&self, target: T) -> owo_colors::Styled<T> {
+        let mut style = owo_colors::Style::new();
+        if let Some(fg) = self.fg {
+            style = style.color(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.on_color(bg);
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        if self.italic {
+            style = style.italic();
+        }
+        if self.underline {
+            style = style.underline();
+        }
+        if self.dim {
+            style = style.dimmed();
+        }
+        style.style(target)
+    }
+
+    const ATTRIBUTES: &'static [&'static str] = &["bold", "italic", "underline", "dim"];
+}
+
+impl std::fmt::Display for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let attributes = [self.bold, self.italic, self.underline, self.dim];
+        let mut parts = Self::ATTRIBUTES
+            .iter()
+            .zip(attributes)
+            .filter(|&(_, set)| set)
+            .map(|(name, _)| Cow::from(*name))
+            .collect::<Vec<_>>();
+
+        match self.fg {
+            Some(fg) => parts.push(fg.name()),
+            None if parts.is_empty() => parts.push(Cow::from("default")),
+            None => {}
+        }
+
+        if let Some(bg) = self.bg {
+            parts.push(Cow::from("on"));
+            parts.push(bg.name());
+        }
+
+        f.write_str(&parts.join(" "))
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut this = Self::default();
+        let mut color = vec![];
+
+        let mut words = input.split_whitespace();
+        for word in words.by_ref() {
+            match &*word.to_ascii_lowercase() {
+                // everything after this is the background
+                "on" => break,
+                "bold" => this.bold = true,
+                "italic" => this.italic = true,
+                "underline" => this.underline = true,
+                "dim" => this.dim = true,
+                _ => color.push(word),
             }
         }
 
-        Some((this, report))
+        this.fg = match &*color.join(" ") {
+            "" | "default" => None,
+            color => Some(color.parse()?),
+        };
+        this.bg = match &*words.collect::<Vec<_>>().join(" ") {
+            "" => None,
+            color => Some(color.parse()?),
+        };
+
+        Ok(this)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Style {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        <Cow<'_, str>>::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl serde::Serialize for Style {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_string().serialize(serializer)
     }
 }
 
+/// A color, either from the terminal's palette or an exact rgb color
+///
+/// Named colors (`BrightRed`) and indexed colors (`ansi(123)`) use the terminal's palette.
+/// Rgb colors (`#ff8800`) are downsampled when the terminal doesn't support truecolor
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct Color(u8, u8, u8);
+pub enum Color {
+    Named(AnsiColors),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
 
 impl owo_colors::DynColor for Color {
     #[inline]
@@ -137,45 +419,100 @@ impl serde::Serialize for Color {
     where
         S: serde::Serializer,
     {
-        self.rgb_to_named()
-            .map_or_else(|| Cow::from(self.to_string()), Cow::from)
-            .serialize(serializer)
+        self.name().serialize(serializer)
     }
 }
 
+// the rgb values are the xterm defaults, they are only used for downsampling
 macro_rules! named_color {
-    ($($s:expr => $ident:ident: ($r:expr, $g:expr, $b:expr))*) => {
-        $( const $ident: Color = Color($r, $g, $b); )*
-        const NAMED_COLORS: &'static [(&'static str, Color)] = &[$(($s, Self::$ident)),*];
+    ($($s:expr => $ident:ident($ansi:ident): ($r:expr, $g:expr, $b:expr))*) => {
+        $( pub const $ident: Color = Color::Named(AnsiColors::$ansi); )*
+        const NAMED_COLORS: &'static [(&'static str, Color, (u8, u8, u8))] = &[
+            $(($s, Self::$ident, ($r, $g, $b))),*
+        ];
     };
 }
 
 impl Color {
     named_color! {
-        "Black"         => BLACK          : (0x40, 0x40, 0x40)
-        "White"         => WHITE          : (0xC0, 0xC0, 0xC0)
-        "Red"           => RED            : (0x80, 0x00, 0x00)
-        "Green"         => GREEN          : (0x00, 0x80, 0x00)
-        "Blue"          => BLUE           : (0x00, 0x00, 0x80)
-        "Yellow"        => YELLOW         : (0x80, 0x80, 0x00)
-        "Magenta"       => MAGENTA        : (0x80, 0x00, 0x80)
-        "Cyan"          => CYAN           : (0x00, 0x80, 0x80)
-        "BrightBlack"   => BRIGHT_BLACK   : (0x80, 0x80, 0x80)
-        "BrightWhite"   => BRIGHT_WHITE   : (0xFF, 0xFF, 0xFF)
-        "BrightRed"     => BRIGHT_RED     : (0xFF, 0x00, 0x00)
-        "BrightGreen"   => BRIGHT_GREEN   : (0x00, 0xFF, 0x00)
-        "BrightBlue"    => BRIGHT_BLUE    : (0x00, 0x00, 0xFF)
-        "BrightYellow"  => BRIGHT_YELLOW  : (0xFF, 0xFF, 0x00)
-        "BrightMagenta" => BRIGHT_MAGENTA : (0xFF, 0x00, 0xFF)
-        "BrightCyan"    => BRIGHT_CYAN    : (0x00, 0xFF, 0xFF)
+        "Black"         => BLACK(Black)                  : (0x00, 0x00, 0x00)
+        "Red"           => RED(Red)                      : (0xCD, 0x00, 0x00)
+        "Green"         => GREEN(Green)                  : (0x00, 0xCD, 0x00)
+        "Yellow"        => YELLOW(Yellow)                : (0xCD, 0xCD, 0x00)
+        "Blue"          => BLUE(Blue)                    : (0x00, 0x00, 0xEE)
+        "Magenta"       => MAGENTA(Magenta)              : (0xCD, 0x00, 0xCD)
+        "Cyan"          => CYAN(Cyan)                    : (0x00, 0xCD, 0xCD)
+        "White"         => WHITE(White)                  : (0xE5, 0xE5, 0xE5)
+        "BrightBlack"   => BRIGHT_BLACK(BrightBlack)     : (0x7F, 0x7F, 0x7F)
+        "BrightRed"     => BRIGHT_RED(BrightRed)         : (0xFF, 0x00, 0x00)
+        "BrightGreen"   => BRIGHT_GREEN(BrightGreen)     : (0x00, 0xFF, 0x00)
+        "BrightYellow"  => BRIGHT_YELLOW(BrightYellow)   : (0xFF, 0xFF, 0x00)
+        "BrightBlue"    => BRIGHT_BLUE(BrightBlue)       : (0x5C, 0x5C, 0xFF)
+        "BrightMagenta" => BRIGHT_MAGENTA(BrightMagenta) : (0xFF, 0x00, 0xFF)
+        "BrightCyan"    => BRIGHT_CYAN(BrightCyan)       : (0x00, 0xFF, 0xFF)
+        "BrightWhite"   => BRIGHT_WHITE(BrightWhite)     : (0xFF, 0xFF, 0xFF)
     }
 }
 
 impl Color {
+    /// The name of the color, or its `ansi(n)` / hex representation
+    pub fn name(self) -> Cow<'static, str> {
+        self.rgb_to_named()
+            .map_or_else(|| Cow::from(self.to_string()), Cow::from)
+    }
+
+    /// The approximate rgb value of this color
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        const LEVELS: [u8; 6] = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+
+        match self {
+            Self::Named(..) => Self::NAMED_COLORS
+                .iter()
+                .find_map(|&(_, color, rgb)| (color == self).then_some(rgb))
+                .unwrap_or((0, 0, 0)),
+            Self::Indexed(n @ 0..=15) => Self::NAMED_COLORS[n as usize].2,
+            Self::Indexed(n @ 16..=231) => {
+                let n = n - 16;
+                let (r, g, b) = (n / 36, (n / 6) % 6, n % 6);
+                (LEVELS[r as usize], LEVELS[g as usize], LEVELS[b as usize])
+            }
+            Self::Indexed(n) => {
+                let gray = 8 + (n - 232) * 10;
+                (gray, gray, gray)
+            }
+            Self::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Converts this color so it can be shown with the color depth
+    pub fn downsample(self, depth: ColorDepth) -> Self {
+        match (self, depth) {
+            (Self::Named(..), _) | (_, ColorDepth::TrueColor) => self,
+            (Self::Indexed(..), ColorDepth::Ansi256) => self,
+            (Self::Rgb(..), ColorDepth::Ansi256) => Self::Indexed(Self::nearest_256(self.to_rgb())),
+            (_, ColorDepth::Ansi16) => Self::nearest_16(self.to_rgb()),
+        }
+    }
+
+    fn nearest_16(rgb: (u8, u8, u8)) -> Self {
+        Self::NAMED_COLORS
+            .iter()
+            .min_by_key(|&&(_, _, named)| distance(rgb, named))
+            .map(|&(_, color, _)| color)
+            .expect("named colors")
+    }
+
+    fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+        // the 6x6x6 cube and the grayscale ramp, the first 16 depend on the terminal's palette
+        (16..=255)
+            .min_by_key(|&n| distance(rgb, Self::Indexed(n).to_rgb()))
+            .expect("non-empty range")
+    }
+
     fn rgb_to_named(self) -> Option<&'static str> {
         Self::NAMED_COLORS
             .iter()
-            .find_map(|&(name, color)| (color == self).then_some(name))
+            .find_map(|&(name, color, _)| (color == self).then_some(name))
     }
 
     fn try_from_named(input: &str) -> Result<Self, String> {
@@ -189,7 +526,7 @@ impl Color {
             l.eq_ignore_ascii_case(r)
         }
 
-        for (named, color) in Self::NAMED_COLORS {
+        for (named, color, _) in Self::NAMED_COLORS {
             if cmp(input, named) {
                 return Ok(*color);
             }
@@ -197,12 +534,35 @@ impl Color {
 
         Err(format!("unknown color: {input}"))
     }
+
+    fn try_from_indexed(input: &str) -> Option<Result<Self, String>> {
+        let index = input
+            .strip_prefix("ansi(")
+            .or_else(|| input.strip_prefix("Ansi("))?
+            .strip_suffix(')')?;
+
+        Some(
+            index
+                .trim()
+                .parse()
+                .map(Self::Indexed)
+                .map_err(|_| format!("invalid ansi index (expected 0-255): {index}")),
+        )
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).unsigned_abs().pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
 }
 
 impl std::fmt::Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Self(r, g, b) = self;
-        write!(f, "#{r:02x}{g:02x}{b:02x}")
+        match self {
+            Self::Named(..) => f.write_str(self.rgb_to_named().unwrap_or_default()),
+            Self::Indexed(n) => write!(f, "ansi({n})"),
+            Self::Rgb(r, g, b) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+        }
     }
 }
 
@@ -211,8 +571,11 @@ impl FromStr for Color {
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         fn is_hex_like(s: &str) -> bool {
-            s.chars()
-                .all(|c| matches!(c, 'a'..='f' | 'A'..='F' | '0'..='9'))
+            s.chars().all(|c| c.is_ascii_hexdigit())
+        }
+
+        if let Some(color) = Self::try_from_indexed(input) {
+            return color;
         }
 
         let input = match input.len() {
@@ -230,13 +593,103 @@ impl FromStr for Color {
             (color & 0xFF) as _,
         );
 
-        Ok(Self(r, g, b))
+        Ok(Self::Rgb(r, g, b))
     }
 }
 
 impl From<Color> for DynColors {
-    fn from(Color(r, g, b): Color) -> Self {
-        Self::Rgb(r, g, b)
+    fn from(color: Color) -> Self {
+        match color.downsample(ColorDepth::get()) {
+            Color::Named(ansi) => Self::Ansi(ansi),
+            Color::Indexed(n) => Self::Xterm(XtermColors::from(n)),
+            Color::Rgb(r, g, b) => Self::Rgb(r, g, b),
+        }
+    }
+}
+
+/// When to color the output
+#[derive(Copy, Clone, Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether `NO_COLOR` or `CLICOLOR_FORCE` are set, these take precedence over the configuration file
+    pub fn is_set_by_env() -> bool {
+        ["NO_COLOR", "CLICOLOR_FORCE"]
+            .into_iter()
+            .any(|key| std::env::var_os(key).is_some_and(|s| !s.is_empty()))
+    }
+}
+
+impl clap::ValueEnum for ColorChoice {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Auto, Self::Always, Self::Never]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        use clap::builder::PossibleValue;
+        Some(match self {
+            Self::Auto => PossibleValue::new("auto"),
+            Self::Always => PossibleValue::new("always"),
+            Self::Never => PossibleValue::new("never"),
+        })
+    }
+}
+
+// NOTE `auto` lets anstream check the terminal, `NO_COLOR`, `CLICOLOR` and `CLICOLOR_FORCE`
+impl From<ColorChoice> for anstream::ColorChoice {
+    fn from(choice: ColorChoice) -> Self {
+        match choice {
+            ColorChoice::Auto => Self::Auto,
+            ColorChoice::Always => Self::Always,
+            ColorChoice::Never => Self::Never,
+        }
+    }
+}
+
+/// How many colors the terminal can show
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+static COLOR_DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+impl ColorDepth {
+    /// The color depth used for rendering, this is detected once
+    pub fn get() -> Self {
+        *COLOR_DEPTH.get_or_init(Self::detect)
+    }
+
+    pub fn detect() -> Self {
+        let var = |key| std::env::var(key).ok().filter(|s| !s.is_empty());
+
+        if var("COLORTERM").is_some_and(|s| matches!(&*s, "truecolor" | "24bit"))
+            || var("WT_SESSION").is_some()
+        {
+            return Self::TrueColor;
+        }
+
+        match var("TERM") {
+            Some(term) if term.contains("truecolor") || term.contains("direct") => Self::TrueColor,
+            Some(term) if term.contains("256") => Self::Ansi256,
+            // these all support truecolor but don't always say so
+            Some(term) if matches!(&*term, "xterm-kitty" | "alacritty" | "wezterm" | "foot") => {
+                Self::TrueColor
+            }
+            _ if var("TERM_PROGRAM")
+                .is_some_and(|s| matches!(&*s, "iTerm.app" | "WezTerm" | "vscode")) =>
+            {
+                Self::TrueColor
+            }
+            _ => Self::Ansi16,
+        }
     }
 }
 
@@ -245,3 +698,21 @@ pub struct Report {
     pub unknown_keys: Vec<String>,
     pub invalid_values: Vec<(String, String)>,
 }
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.unknown_keys.is_empty() && self.invalid_values.is_empty()
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for key in &self.unknown_keys {
+            writeln!(f, "unknown key: {key}")?;
+        }
+        for (key, err) in &self.invalid_values {
+            writeln!(f, "invalid value for {key}: {err}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(unix)]
+
+use std::{io::BufReader, os::unix::fs::PermissionsExt as _, path::PathBuf};
+
+use ccs::{
+    iter_reasons, Cargo, CargoSource, Command, Extra, Features, Level, Options, Reason, Target,
+    Tool,
+};
+
+// writes the json cargo would, with the arguments it got as the message
+const STUB: &str = r#"#!/bin/sh
+cat <<EOF
+{"reason":"compiler-artifact","package_id":"stub 0.1.0"}
+{"reason":"compiler-message","package_id":"stub 0.1.0","message":{"message":"$*","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"src/main.rs","byte_start":15,"byte_end":16,"line_start":1,"line_end":1,"column_start":16,"column_end":17,"is_primary":true,"text":[{"text":"fn main() { let x = 1; }","highlight_start":16,"highlight_end":17}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"warning: unused variable"}}
+{"reason":"build-finished","success":true}
+EOF
+"#;
+
+#[test]
+fn wrapper_json_is_parsed() {
+    // NOTE the space checks that the program from a list isn't split again
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("wrapper stub");
+    std::fs::create_dir_all(&dir).unwrap();
+    let stub = dir.join("cross");
+    std::fs::write(&stub, STUB).unwrap();
+    std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let opts = Options {
+        extra: Extra::default(),
+        path: None,
+        cargo: Cargo {
+            path: PathBuf::from("cargo"),
+            source: CargoSource::Path,
+        },
+        wrapper: vec![stub.to_string_lossy().into_owned(), "--stub".to_string()],
+        target: Target::Default,
+        features: Features::Default,
+        dry_run: false,
+        tool: Tool::Clippy,
+        cargo_args: vec![],
+        lint_args: vec![],
+        separate_target_dir: false,
+        fresh: false,
+        timeout: None,
+        color: false,
+        progress: false,
+    };
+
+    let mut process = Command::annoying()
+        .build_command(opts)
+        .unwrap()
+        .expect("this isn't a dry run");
+    let reasons = iter_reasons(BufReader::new(process.take_stdout())).collect::<Vec<_>>();
+    let finished = process.finish();
+    assert!(finished.status.is_some_and(|status| status.success()));
+
+    let [Reason::CompilerMessage { message }] = &*reasons else {
+        panic!("expected one compiler message, got: {reasons:?}");
+    };
+    assert!(matches!(message.level, Level::Warning));
+    assert_eq!(message.code.as_ref().unwrap().code, "unused_variables");
+    assert_eq!(message.spans[0].location(), "src/main.rs:1:16");
+
+    // the wrapper gets its own arguments first, then what cargo would have
+    assert!(
+        message
+            .message
+            .starts_with("--stub clippy --message-format=json"),
+        "{}",
+        message.message
+    );
+    assert!(
+        message
+            .message
+            .ends_with("-- -W clippy::all -W clippy::nursery"),
+        "{}",
+        message.message
+    );
+}